@@ -0,0 +1,17 @@
+use std::{env, process::Command};
+
+fn main() {
+    // Record the target and compiler version for `uiua version --verbose`
+    let target = env::var("TARGET").unwrap_or_else(|_| "unknown".into());
+    println!("cargo:rustc-env=UIUA_BUILD_TARGET={target}"); // Allow println
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|version| version.trim().to_string())
+        .unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=UIUA_RUSTC_VERSION={rustc_version}"); // Allow println
+    println!("cargo:rerun-if-changed=build.rs"); // Allow println
+}
@@ -183,6 +183,12 @@ impl SysBackend for WebBackend {
         });
         Ok(())
     }
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        FILES.with(|files| {
+            (files.borrow_mut().entry(path.into()).or_default()).extend_from_slice(contents)
+        });
+        Ok(())
+    }
     fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
         self.file(path, |contents| contents.to_vec())
     }
@@ -268,12 +274,32 @@ impl SysBackend for WebBackend {
         Ok(data)
     }
     fn delete(&self, path: &str) -> Result<(), String> {
-        FILES.with(|files| files.borrow_mut().remove(Path::new(path)));
-        Ok(())
+        FILES
+            .with(|files| files.borrow_mut().remove(Path::new(path)))
+            .map(drop)
+            .ok_or_else(|| format!("{path} does not exist"))
     }
     fn trash(&self, path: &str) -> Result<(), String> {
         self.delete(path)
     }
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        FILES.with(|files| {
+            let mut files = files.borrow_mut();
+            let contents =
+                (files.remove(Path::new(from))).ok_or_else(|| format!("{from} does not exist"))?;
+            files.insert(to.into(), contents);
+            Ok(())
+        })
+    }
+    fn copy_file(&self, from: &str, to: &str) -> Result<(), String> {
+        FILES.with(|files| {
+            let mut files = files.borrow_mut();
+            let contents = (files.get(Path::new(from)).cloned())
+                .ok_or_else(|| format!("{from} does not exist"))?;
+            files.insert(to.into(), contents);
+            Ok(())
+        })
+    }
     fn play_audio(&self, wav_bytes: Vec<u8>, label: Option<&str>) -> Result<(), String> {
         (self.stdout.lock().unwrap()).push(OutputItem::Audio(wav_bytes, label.map(Into::into)));
         Ok(())
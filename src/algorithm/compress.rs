@@ -0,0 +1,44 @@
+//! Compression and decompression of byte arrays
+
+#[allow(unused_imports)]
+use crate::{Array, Uiua, UiuaResult, Value};
+
+pub(crate) fn gzip(env: &mut Uiua) -> UiuaResult {
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::Write;
+
+        use flate2::{write::GzEncoder, Compression};
+        let data = match env.pop(1)? {
+            Value::Char(arr) if arr.rank() <= 1 => arr.data.iter().collect::<String>().into_bytes(),
+            value => value.as_bytes(env, "Compressed data must be a string or a list of bytes")?,
+        };
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&data).map_err(|e| env.error(e))?;
+        let bytes = encoder.finish().map_err(|e| env.error(e))?;
+        env.push(Array::<u8>::from(bytes.as_slice()));
+        Ok(())
+    }
+    #[cfg(not(feature = "gzip"))]
+    Err(env.error("Gzip compression is not supported in this environment"))
+}
+
+pub(crate) fn ungzip(env: &mut Uiua) -> UiuaResult {
+    #[cfg(feature = "gzip")]
+    {
+        use std::io::Read;
+
+        use flate2::read::GzDecoder;
+        let data = env
+            .pop(1)?
+            .as_bytes(env, "Gzip data must be a list of bytes")?;
+        let mut bytes = Vec::new();
+        GzDecoder::new(data.as_slice())
+            .read_to_end(&mut bytes)
+            .map_err(|e| env.error(format!("Invalid gzip data: {e}")))?;
+        env.push(Array::<u8>::from(bytes.as_slice()));
+        Ok(())
+    }
+    #[cfg(not(feature = "gzip"))]
+    Err(env.error("Gzip decompression is not supported in this environment"))
+}
@@ -3,26 +3,81 @@
 #[cfg(feature = "audio_encode")]
 use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 #[cfg(feature = "image")]
-use image::{DynamicImage, ImageOutputFormat};
+use image::{DynamicImage, ImageFormat, ImageOutputFormat};
 
 #[allow(unused_imports)]
 use crate::{Array, Uiua, UiuaResult, Value};
 
+pub(crate) fn hash(env: &mut Uiua) -> UiuaResult {
+    #[cfg(feature = "hash")]
+    {
+        use md5::Md5;
+        use sha1::Sha1;
+        use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+        let algorithm = env
+            .pop(1)?
+            .as_string(env, "Hash algorithm must be a string")?;
+        let data = match env.pop(2)? {
+            Value::Char(arr) if arr.rank() <= 1 => arr.data.iter().collect::<String>().into_bytes(),
+            value => value.as_bytes(env, "Hashed data must be a string or a list of bytes")?,
+        };
+        let digest = match algorithm.as_str() {
+            "md5" => Md5::digest(&data).to_vec(),
+            "sha1" => Sha1::digest(&data).to_vec(),
+            "sha224" => Sha224::digest(&data).to_vec(),
+            "sha256" => Sha256::digest(&data).to_vec(),
+            "sha384" => Sha384::digest(&data).to_vec(),
+            "sha512" => Sha512::digest(&data).to_vec(),
+            _ => {
+                return Err(env.error(format!(
+                    "Unknown hash algorithm {algorithm:?}. \
+                    Supported algorithms are md5, sha1, sha224, sha256, sha384, and sha512"
+                )))
+            }
+        };
+        env.push(Array::<u8>::from(digest.as_slice()));
+        Ok(())
+    }
+    #[cfg(not(feature = "hash"))]
+    Err(env.error("Hashing is not supported in this environment"))
+}
+
 pub(crate) fn image_encode(env: &mut Uiua) -> UiuaResult {
     #[cfg(feature = "image")]
     {
-        let format = env
-            .pop(1)?
-            .as_string(env, "Image format must be a string")?;
+        let (format, quality) = match env.pop(1)? {
+            Value::Box(arr) if arr.rank() == 1 && arr.row_count() == 2 => {
+                let format = (arr.data[0].0).as_string(env, "Image format must be a string")?;
+                let quality =
+                    (arr.data[1].0).as_nat(env, "Image quality must be a natural number")?;
+                if !(1..=100).contains(&quality) {
+                    return Err(env.error(format!(
+                        "Image quality must be between 1 and 100, but it is {quality}"
+                    )));
+                }
+                (format, Some(quality as u8))
+            }
+            format => (
+                format.as_string(env, "Image format must be a string")?,
+                None,
+            ),
+        };
         let value = env.pop(2)?;
-        let output_format = match format.as_str() {
-            "jpg" | "jpeg" => ImageOutputFormat::Jpeg(100),
-            "png" => ImageOutputFormat::Png,
-            "bmp" => ImageOutputFormat::Bmp,
-            "gif" => ImageOutputFormat::Gif,
-            "ico" => ImageOutputFormat::Ico,
-            "qoi" => ImageOutputFormat::Qoi,
-            format => return Err(env.error(format!("Invalid image format: {}", format))),
+        let output_format = match image_format(&format) {
+            Some(ImageFormat::Jpeg) => ImageOutputFormat::Jpeg(quality.unwrap_or(100)),
+            Some(image_format) => {
+                if quality.is_some() {
+                    let message = format!("Image quality is ignored for {format} images");
+                    let report = crate::Report::new_multi(
+                        crate::ReportKind::Diagnostic(crate::DiagnosticKind::Warning),
+                        env.inputs(),
+                        [(message, env.span())],
+                    );
+                    env.rt.reports.push(report);
+                }
+                image_format.into()
+            }
+            None => return Err(env.error(format!("Invalid image format: {}", format))),
         };
         let bytes =
             crate::encode::value_to_image_bytes(&value, output_format).map_err(|e| env.error(e))?;
@@ -33,10 +88,57 @@ pub(crate) fn image_encode(env: &mut Uiua) -> UiuaResult {
     Err(env.error("Image encoding is not supported in this environment"))
 }
 
+/// Look up one of the image formats supported by [`img`](crate::Primitive::ImageEncode) by name
+#[cfg(feature = "image")]
+fn image_format(name: &str) -> Option<ImageFormat> {
+    Some(match name {
+        "jpg" | "jpeg" => ImageFormat::Jpeg,
+        "png" => ImageFormat::Png,
+        "bmp" => ImageFormat::Bmp,
+        "gif" => ImageFormat::Gif,
+        "ico" => ImageFormat::Ico,
+        "qoi" => ImageFormat::Qoi,
+        _ => return None,
+    })
+}
+
 pub(crate) fn image_decode(env: &mut Uiua) -> UiuaResult {
     #[cfg(feature = "image")]
     {
-        let bytes: crate::cowslice::CowSlice<u8> = match env.pop(1)? {
+        // The bytes may be preceded by a format and/or a channel count
+        let (mut forced_format, mut channels) = (None, None);
+        let bytes = match env.pop(1)? {
+            Value::Box(arr) if arr.rank() == 1 && (2..=3).contains(&arr.row_count()) => {
+                let (options, bytes) = arr.data.split_at(arr.data.len() - 1);
+                for option in options.iter().map(|b| &b.0) {
+                    if let Value::Char(_) = option {
+                        if forced_format.is_some() {
+                            return Err(env.error("Image format cannot be given twice"));
+                        }
+                        let name = option.as_string(env, "Image format must be a string")?;
+                        forced_format =
+                            Some(image_format(&name).ok_or_else(|| {
+                                env.error(format!("Invalid image format: {name}"))
+                            })?);
+                    } else {
+                        if channels.is_some() {
+                            return Err(env.error("Image channel count cannot be given twice"));
+                        }
+                        let n =
+                            option.as_nat(env, "Image channel count must be a natural number")?;
+                        if !(1..=4).contains(&n) {
+                            return Err(env.error(format!(
+                                "Image channel count must be between 1 and 4, but it is {n}"
+                            )));
+                        }
+                        channels = Some(n);
+                    }
+                }
+                bytes[0].0.clone()
+            }
+            bytes => bytes,
+        };
+        let bytes: crate::cowslice::CowSlice<u8> = match bytes {
             Value::Byte(arr) => {
                 if arr.rank() != 1 {
                     return Err(env.error(format!(
@@ -57,9 +159,13 @@ pub(crate) fn image_decode(env: &mut Uiua) -> UiuaResult {
             }
             _ => return Err(env.error("Image bytes must be a numeric array")),
         };
-        let format = image::guess_format(&bytes)
-            .map_err(|e| env.error(format!("Failed to read image: {e}")))?;
-        let array = crate::encode::image_bytes_to_array(&bytes, true).map_err(|e| env.error(e))?;
+        let format = match forced_format {
+            Some(format) => format,
+            None => image::guess_format(&bytes)
+                .map_err(|e| env.error(format!("Failed to read image: {e}")))?,
+        };
+        let array = crate::encode::image_bytes_to_array_native(&bytes, forced_format, channels)
+            .map_err(|e| env.error(e))?;
         env.push(array);
         env.push(match format {
             image::ImageFormat::Jpeg => "jpeg".into(),
@@ -205,6 +311,41 @@ pub fn image_bytes_to_array(bytes: &[u8], alpha: bool) -> Result<Array<f64>, Str
     })
 }
 
+#[doc(hidden)]
+#[cfg(feature = "image")]
+pub fn image_bytes_to_array_native(
+    bytes: &[u8],
+    format: Option<ImageFormat>,
+    channels: Option<usize>,
+) -> Result<Array<f64>, String> {
+    use image::ColorType;
+    let image = match format {
+        Some(format) => image::load_from_memory_with_format(bytes, format),
+        None => image::load_from_memory(bytes),
+    }
+    .map_err(|e| format!("Failed to read image: {}", e))?;
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let channels = channels.unwrap_or(match image.color() {
+        ColorType::L8 | ColorType::L16 => 1,
+        ColorType::La8 | ColorType::La16 => 2,
+        ColorType::Rgb8 | ColorType::Rgb16 | ColorType::Rgb32F => 3,
+        _ => 4,
+    });
+    let raw = match channels {
+        1 => image.into_luma8().into_raw(),
+        2 => image.into_luma_alpha8().into_raw(),
+        3 => image.into_rgb8().into_raw(),
+        4 => image.into_rgba8().into_raw(),
+        n => return Err(format!("Invalid image channel count {n}")),
+    };
+    Ok(Array::new(
+        [height, width, channels],
+        (raw.into_iter())
+            .map(|b| b as f64 / 255.0)
+            .collect::<crate::cowslice::CowSlice<_>>(),
+    ))
+}
+
 #[doc(hidden)]
 #[cfg(feature = "image")]
 pub fn image_to_bytes(image: &DynamicImage, format: ImageOutputFormat) -> Result<Vec<u8>, String> {
@@ -215,9 +356,9 @@ pub fn image_to_bytes(image: &DynamicImage, format: ImageOutputFormat) -> Result
     Ok(bytes.into_inner())
 }
 
+/// Check that a value can be encoded as an image, returning its height, width, and channel count
 #[doc(hidden)]
-#[cfg(feature = "image")]
-pub fn value_to_image(value: &Value) -> Result<DynamicImage, String> {
+pub fn image_dims(value: &Value) -> Result<[usize; 3], String> {
     if ![2, 3].contains(&value.rank()) {
         return Err(format!(
             "Image must be a rank 2 or 3 numeric array, but it is a rank-{} {} array",
@@ -225,17 +366,32 @@ pub fn value_to_image(value: &Value) -> Result<DynamicImage, String> {
             value.type_name()
         ));
     }
-    let bytes = match value {
-        Value::Num(nums) => nums.data.iter().map(|f| (*f * 255.0) as u8).collect(),
-        Value::Byte(bytes) => bytes.data.iter().map(|&b| (b > 0) as u8 * 255).collect(),
-        _ => return Err("Image must be a numeric array".into()),
-    };
+    if !matches!(value, Value::Num(_) | Value::Byte(_)) {
+        return Err("Image must be a numeric array".into());
+    }
     #[allow(clippy::match_ref_pats)]
     let [height, width, px_size] = match value.shape().dims() {
         &[a, b] => [a, b, 1],
         &[a, b, c] => [a, b, c],
         _ => unreachable!("Shape checked above"),
     };
+    if !(1..=4).contains(&px_size) {
+        return Err(format!(
+            "For a color image, the last dimension of the image array must be between 1 and 4 but it is {px_size}"
+        ));
+    }
+    Ok([height, width, px_size])
+}
+
+#[doc(hidden)]
+#[cfg(feature = "image")]
+pub fn value_to_image(value: &Value) -> Result<DynamicImage, String> {
+    let [height, width, px_size] = image_dims(value)?;
+    let bytes = match value {
+        Value::Num(nums) => nums.data.iter().map(|f| (*f * 255.0) as u8).collect(),
+        Value::Byte(bytes) => bytes.data.iter().map(|&b| (b > 0) as u8 * 255).collect(),
+        _ => unreachable!("Type checked above"),
+    };
     Ok(match px_size {
         1 => image::GrayImage::from_raw(width as u32, height as u32, bytes)
             .ok_or("Failed to create image")?
@@ -249,11 +405,7 @@ pub fn value_to_image(value: &Value) -> Result<DynamicImage, String> {
         4 => image::RgbaImage::from_raw(width as u32, height as u32, bytes)
             .ok_or("Failed to create image")?
             .into(),
-        n => {
-            return Err(format!(
-                "For a color image, the last dimension of the image array must be between 1 and 4 but it is {n}"
-            ))
-        }
+        _ => unreachable!("Channel count checked above"),
     })
 }
 
@@ -221,6 +221,7 @@ fn prim_inverse(prim: Primitive, span: usize) -> Option<Instr> {
         Json => Instr::ImplPrim(UnJson, span),
         Csv => Instr::ImplPrim(UnCsv, span),
         Xlsx => Instr::ImplPrim(UnXlsx, span),
+        Gzip => Instr::ImplPrim(UnGzip, span),
         Fft => Instr::ImplPrim(UnFft, span),
         DateTime => Instr::ImplPrim(UnDatetime, span),
         _ => return None,
@@ -251,6 +252,7 @@ fn impl_prim_inverse(prim: ImplPrimitive, span: usize) -> Option<Instr> {
         UnJson => Instr::Prim(Json, span),
         UnCsv => Instr::Prim(Csv, span),
         UnXlsx => Instr::Prim(Xlsx, span),
+        UnGzip => Instr::Prim(Gzip, span),
         UnFft => Instr::Prim(Fft, span),
         ImageDecode => Instr::Prim(ImageEncode, span),
         GifDecode => Instr::Prim(GifEncode, span),
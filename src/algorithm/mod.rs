@@ -21,6 +21,7 @@ use crate::{
     Value,
 };
 
+pub mod compress;
 mod dyadic;
 pub mod encode;
 pub mod invert;
@@ -29,6 +30,7 @@ pub mod map;
 mod monadic;
 pub mod permute;
 pub mod pervade;
+pub mod plot;
 pub mod reduce;
 pub mod table;
 pub mod zip;
@@ -101,7 +103,9 @@ impl fmt::Display for FillShapeError {
 }
 
 pub fn validate_size<T>(sizes: impl IntoIterator<Item = usize>, env: &Uiua) -> UiuaResult<usize> {
-    validate_size_of::<T>(sizes).map_err(|e| env.error(e))
+    // A configured limit can only lower the default one
+    let max = (env.rt.max_memory).map_or_else(default_max_size, |max| max.min(default_max_size()));
+    validate_size_with_max(size_of::<T>(), sizes, max).map_err(|e| env.error(e))
 }
 
 pub fn validate_size_of<T>(sizes: impl IntoIterator<Item = usize>) -> Result<usize, SizeError> {
@@ -111,6 +115,23 @@ pub fn validate_size_of<T>(sizes: impl IntoIterator<Item = usize>) -> Result<usi
 pub(crate) fn validate_size_impl(
     elem_size: usize,
     sizes: impl IntoIterator<Item = usize>,
+) -> Result<usize, SizeError> {
+    validate_size_with_max(elem_size, sizes, default_max_size())
+}
+
+fn default_max_size() -> usize {
+    let max_mega = if cfg!(target_arch = "wasm32") {
+        256
+    } else {
+        4096
+    };
+    max_mega * 1024usize.pow(2)
+}
+
+fn validate_size_with_max(
+    elem_size: usize,
+    sizes: impl IntoIterator<Item = usize>,
+    max: usize,
 ) -> Result<usize, SizeError> {
     let mut elements = 1.0;
     for size in sizes {
@@ -120,12 +141,7 @@ pub(crate) fn validate_size_impl(
         elements *= size as f64;
     }
     let size = elements * elem_size as f64;
-    let max_mega = if cfg!(target_arch = "wasm32") {
-        256
-    } else {
-        4096
-    };
-    if size > (max_mega * 1024usize.pow(2)) as f64 {
+    if size > max as f64 {
         return Err(SizeError(elements));
     }
     Ok(elements as usize)
@@ -1788,7 +1788,12 @@ impl Array<f64> {
 }
 
 impl Value {
-    pub(crate) fn to_json_string(&self, env: &Uiua) -> UiuaResult<String> {
+    /// Serialize the value as a JSON string
+    ///
+    /// Character lists become strings, and other arrays become nested JSON arrays.
+    /// Boxes are serialized as their contents.
+    /// Since JSON cannot represent them, `NaN` and infinities become `null`.
+    pub fn to_json_string(&self, env: &Uiua) -> UiuaResult<String> {
         let json = self.to_json_value(env)?;
         serde_json::to_string(&json).map_err(|e| env.error(e))
     }
@@ -1836,7 +1841,12 @@ impl Value {
             }
         })
     }
-    pub(crate) fn from_json_string(json: &str, env: &Uiua) -> UiuaResult<Self> {
+    /// Parse a JSON string into a value
+    ///
+    /// Objects become maps, and arrays become arrays if their items are compatible or lists of boxes if they are not.
+    /// `null` becomes `NaN`.
+    /// Malformed JSON produces an error rather than a panic.
+    pub fn from_json_string(json: &str, env: &Uiua) -> UiuaResult<Self> {
         #[cfg(not(feature = "json5"))]
         let json_value: serde_json::Value = serde_json::from_str(json).map_err(|e| env.error(e))?;
         #[cfg(feature = "json5")]
@@ -0,0 +1,155 @@
+//! Rendering numeric arrays to images
+
+use ecow::EcoVec;
+
+use crate::{algorithm::validate_size, Array, Boxed, Shape, Uiua, UiuaResult, Value};
+
+const DEFAULT_SIZE: [usize; 2] = [300, 400];
+const MARGIN: f64 = 10.0;
+const BACKGROUND: [f64; 4] = [1.0, 1.0, 1.0, 1.0];
+const AXIS_COLOR: [f64; 4] = [0.7, 0.7, 0.7, 1.0];
+const LINE_COLOR: [f64; 4] = [0.0, 0.45, 0.85, 1.0];
+
+/// Render a list of numbers as a line chart
+pub(crate) fn plot(env: &mut Uiua) -> UiuaResult {
+    let (size, data) = match env.pop(1)? {
+        Value::Box(arr) if arr.shape == [2] => {
+            let Boxed(size) = arr.data[0].clone();
+            let Boxed(data) = arr.data[1].clone();
+            (Some(size), data)
+        }
+        val => (None, val),
+    };
+    let [height, width] = match size {
+        Some(size) => {
+            let size = size.as_nats(env, "Plot size must be a list of natural numbers")?;
+            let [height, width] = size[..] else {
+                return Err(env.error(format!(
+                    "Plot size must be a list of 2 numbers, but it has {}",
+                    size.len()
+                )));
+            };
+            [height, width]
+        }
+        None => DEFAULT_SIZE,
+    };
+    if data.rank() != 1 {
+        return Err(env.error(format!(
+            "Plotted data must be a rank 1 array, but it is rank {}",
+            data.rank()
+        )));
+    }
+    let data = data.as_nums(env, "Plotted data must be numbers")?;
+    let elem_count = validate_size::<f64>([height, width, 4], env)?;
+
+    let mut canvas = Canvas {
+        width,
+        height,
+        data: BACKGROUND
+            .iter()
+            .copied()
+            .cycle()
+            .take(elem_count)
+            .collect(),
+    };
+
+    // Find the range of the data, ignoring values that cannot be drawn
+    let (mut min, mut max) = data
+        .iter()
+        .copied()
+        .filter(|n| n.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), n| {
+            (min.min(n), max.max(n))
+        });
+    if min > max {
+        (min, max) = (0.0, 0.0);
+    }
+    if min == max {
+        (min, max) = (min - 0.5, max + 0.5);
+    }
+    let (left, right) = (MARGIN, (width as f64 - MARGIN).max(MARGIN));
+    let (top, bottom) = (MARGIN, (height as f64 - MARGIN).max(MARGIN));
+    let x_of = |i: usize| {
+        if data.len() <= 1 {
+            (left + right) / 2.0
+        } else {
+            left + (right - left) * i as f64 / (data.len() - 1) as f64
+        }
+    };
+    let y_of = |n: f64| bottom - (bottom - top) * (n - min) / (max - min);
+
+    // Draw the x axis if it is in range
+    if min <= 0.0 && 0.0 <= max {
+        canvas.line((left, y_of(0.0)), (right, y_of(0.0)), 0.5, AXIS_COLOR);
+    }
+
+    // Draw the line, leaving gaps at non-finite values
+    let points: Vec<Option<(f64, f64)>> = data
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| n.is_finite().then(|| (x_of(i), y_of(n))))
+        .collect();
+    for (i, point) in points.iter().enumerate() {
+        let Some(a) = *point else {
+            continue;
+        };
+        match points.get(i + 1) {
+            Some(Some(b)) => canvas.line(a, *b, 1.0, LINE_COLOR),
+            // Isolated points are still drawn
+            _ if i == 0 || points[i - 1].is_none() => canvas.line(a, a, 1.5, LINE_COLOR),
+            _ => {}
+        }
+    }
+
+    env.push(Array::new(Shape::from([height, width, 4]), canvas.data));
+    Ok(())
+}
+
+/// An RGBA canvas
+struct Canvas {
+    width: usize,
+    height: usize,
+    data: EcoVec<f64>,
+}
+
+impl Canvas {
+    /// Blend a color into a pixel with the given coverage
+    fn blend(&mut self, x: isize, y: isize, coverage: f64, color: [f64; 4]) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        let alpha = color[3] * coverage.clamp(0.0, 1.0);
+        let start = (y as usize * self.width + x as usize) * 4;
+        let pixel = &mut self.data.make_mut()[start..][..4];
+        for (channel, &c) in pixel.iter_mut().zip(&color[..3]) {
+            *channel = *channel * (1.0 - alpha) + c * alpha;
+        }
+        pixel[3] += alpha * (1.0 - pixel[3]);
+    }
+    /// Draw an antialiased line segment with the given half-thickness
+    fn line(&mut self, (x0, y0): (f64, f64), (x1, y1): (f64, f64), radius: f64, color: [f64; 4]) {
+        let min_x = (x0.min(x1) - radius - 1.0).floor() as isize;
+        let max_x = (x0.max(x1) + radius + 1.0).ceil() as isize;
+        let min_y = (y0.min(y1) - radius - 1.0).floor() as isize;
+        let max_y = (y0.max(y1) + radius + 1.0).ceil() as isize;
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let len_sq = dx * dx + dy * dy;
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // Distance from the pixel center to the segment
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                let t = if len_sq == 0.0 {
+                    0.0
+                } else {
+                    (((px - x0) * dx + (py - y0) * dy) / len_sq).clamp(0.0, 1.0)
+                };
+                let (cx, cy) = (x0 + t * dx, y0 + t * dy);
+                let dist = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+                let coverage = radius + 0.5 - dist;
+                if coverage > 0.0 {
+                    self.blend(x, y, coverage, color);
+                }
+            }
+        }
+    }
+}
@@ -6,9 +6,15 @@ use serde::*;
 
 use crate::{
     is_ident_char, CodeSpan, FuncSlice, Function, InputSrc, Instr, IntoInputSrc, LocalName, Module,
-    Signature, Span, Uiua, UiuaResult, Value,
+    Signature, Span, Uiua, UiuaResult, Value, VERSION,
 };
 
+/// The first line of every `.uasm` file
+///
+/// It is followed by the version of Uiua that built the assembly.
+/// Assemblies are only loaded by the same version that built them.
+pub const UASM_HEADER: &str = "UIUA ASSEMBLY";
+
 /// A compiled Uiua assembly
 #[derive(Clone)]
 pub struct Assembly {
@@ -62,6 +68,10 @@ impl Assembly {
         );
         &self.instrs[slice.start..end]
     }
+    /// Get the inputs that were compiled into the assembly
+    pub fn inputs(&self) -> &Inputs {
+        &self.inputs
+    }
     /// Get the mutable instructions of a function slice
     pub fn instrs_mut(&mut self, slice: FuncSlice) -> &mut [Instr] {
         &mut self.instrs.make_mut()[slice.start..slice.end()]
@@ -191,8 +201,22 @@ impl Assembly {
         self.top_slices.clear();
     }
     /// Parse a `.uasm` file into an assembly
+    ///
+    /// Fails if the assembly was built by a different version of Uiua
     pub fn from_uasm(src: &str) -> Result<Self, String> {
-        let rest = src;
+        let (header, rest) = src.split_once('\n').unwrap_or((src, ""));
+        let Some(version) = header.trim().strip_prefix(UASM_HEADER) else {
+            return Err("Missing assembly header. The assembly may have been \
+                built by an older version of Uiua. Rebuild it with `uiua build`."
+                .into());
+        };
+        let version = version.trim();
+        if version != VERSION {
+            return Err(format!(
+                "Assembly was built by Uiua {version}, but this is Uiua {VERSION}. \
+                Rebuild it with `uiua build`."
+            ));
+        }
         let (instrs_src, rest) = rest
             .trim()
             .split_once("TOP SLICES")
@@ -220,7 +244,7 @@ impl Assembly {
                     serde_json::from_str(&json).map_err(|_| e)
                 })
                 .or_else(|e| serde_json::from_str(&format!("\"{line}\"")).map_err(|_| e))
-                .unwrap();
+                .map_err(|e| format!("Invalid instruction {line:?}: {e}"))?;
             instrs.push(instr);
         }
 
@@ -306,7 +330,7 @@ impl Assembly {
     }
     /// Serialize the assembly into a `.uasm` file
     pub fn to_uasm(&self) -> String {
-        let mut uasm = String::new();
+        let mut uasm = format!("{UASM_HEADER} {VERSION}\n");
 
         for instr in &self.instrs {
             let json = serde_json::to_value(instr).unwrap();
@@ -3,6 +3,8 @@
 use core::mem::discriminant;
 use std::{borrow::Cow, fmt};
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     function::{FunctionId, Signature},
     lex::{CodeSpan, Sp},
@@ -11,7 +13,7 @@ use crate::{
 };
 
 /// A top-level item
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Item {
     /// Just some code
     Words(Vec<Vec<Sp<Word>>>),
@@ -26,7 +28,7 @@ pub enum Item {
 }
 
 /// A binding
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Binding {
     /// The name of the binding
     pub name: Sp<Ident>,
@@ -54,7 +56,7 @@ impl Binding {
 }
 
 /// A scoped module
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScopedModule {
     /// The span of the opening delimiter
     pub open_span: CodeSpan,
@@ -69,7 +71,7 @@ pub struct ScopedModule {
 }
 
 /// The kind of a module
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ModuleKind {
     /// A named module
     Named(Sp<Ident>),
@@ -78,7 +80,7 @@ pub enum ModuleKind {
 }
 
 /// An import
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Import {
     /// The name given to the imported module
     pub name: Option<Sp<Ident>>,
@@ -90,7 +92,7 @@ pub struct Import {
     pub lines: Vec<Option<ImportLine>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// A line of imported items
 pub struct ImportLine {
     /// The span of the ~
@@ -116,7 +118,7 @@ impl Import {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// A data definition
 pub struct DataDef {
     /// The span of the ~ or |
@@ -131,7 +133,7 @@ pub struct DataDef {
     pub func: Option<Vec<Sp<Word>>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// The fields of a data definition
 pub struct DataFields {
     /// Whether the array is boxed
@@ -144,7 +146,7 @@ pub struct DataFields {
     pub close_span: Option<CodeSpan>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// A data field
 pub struct DataField {
     /// The name of the field
@@ -155,7 +157,7 @@ pub struct DataField {
     pub bar_span: Option<CodeSpan>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// A data field default value
 pub struct FieldDefault {
     /// The span of the assignment arrow
@@ -207,7 +209,7 @@ impl DataFields {
 }
 
 /// A word
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub enum Word {
     Number(String, f64),
@@ -364,7 +366,7 @@ impl fmt::Debug for Word {
 }
 
 /// A placeholder operation
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PlaceholderOp {
     /// Pop and inline the top operand
     Call,
@@ -404,7 +406,7 @@ impl fmt::Display for PlaceholderOp {
 }
 
 /// A refered-to item
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Ref {
     /// The module path of the item
     pub path: Vec<RefComponent>,
@@ -417,7 +419,7 @@ pub struct Ref {
 }
 
 /// A component of a reference
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RefComponent {
     /// The name of the module
     pub module: Sp<Ident>,
@@ -467,7 +469,7 @@ impl fmt::Display for Ref {
 }
 
 /// A stack array notation term
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Arr {
     /// The array's inner signature
     pub signature: Option<Sp<Signature>>,
@@ -492,7 +494,7 @@ impl fmt::Debug for Arr {
 }
 
 /// An inline function
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Func {
     /// The function's id
     pub id: FunctionId,
@@ -521,7 +523,7 @@ impl fmt::Debug for Func {
 }
 
 /// A function pack
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionPack {
     /// The branches of the pack
     pub branches: Vec<Sp<Func>>,
@@ -532,7 +534,7 @@ pub struct FunctionPack {
 }
 
 /// A modifier with operands
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Modified {
     /// The modifier itself
     pub modifier: Sp<Modifier>,
@@ -558,7 +560,7 @@ impl fmt::Debug for Modified {
 }
 
 /// A modifier
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Modifier {
     /// A primitive modifier
     Primitive(Primitive),
@@ -595,7 +597,7 @@ impl Modifier {
 }
 
 /// A subscript
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Subscript {
     /// The subscript number
     pub n: Sp<Option<usize>>,
@@ -6,6 +6,20 @@ impl Compiler {
     pub(super) fn binding(&mut self, binding: Binding, prelude: BindingPrelude) -> UiuaResult {
         let public = binding.public;
 
+        if (self.denied_categories).contains(&DiagnosticCategory::Shadowing)
+            && self.scope.names.contains_key(&binding.name.value)
+        {
+            self.emit_categorized_diagnostic(
+                format!(
+                    "`{}` shadows an earlier binding with the same name",
+                    binding.name.value
+                ),
+                DiagnosticKind::Warning,
+                DiagnosticCategory::Shadowing,
+                binding.name.span.clone(),
+            );
+        }
+
         // Alias re-bound imports
         if ident_modifier_args(&binding.name.value) == 0
             && binding.words.iter().filter(|w| w.value.is_code()).count() == 1
@@ -247,10 +261,11 @@ impl Compiler {
                             if let Ok(rest_sig) = instrs_signature(rest) {
                                 if rest_sig.args == sig.args && rest_sig.outputs + 1 == sig.outputs
                                 {
-                                    comp.emit_diagnostic(
+                                    comp.emit_categorized_diagnostic(
                                         "Functions should consume their arguments. \
                                         Try removing this.",
                                         DiagnosticKind::Style,
+                                        DiagnosticCategory::StackResidue,
                                         dup_span,
                                     );
                                 }
@@ -487,6 +502,23 @@ impl Compiler {
         prev_com: Option<EcoString>,
     ) -> UiuaResult {
         let m = m.value;
+        if let ModuleKind::Test = m.kind {
+            if !self.test_scope_matches(&m.items) {
+                self.skipped_test_scopes += 1;
+                return Ok(());
+            }
+            let label = m.items.iter().find_map(|item| match item {
+                Item::Words(lines) => lines.iter().flatten().find_map(|word| match &word.value {
+                    Word::Comment(comment) => Some(comment.trim().to_string()),
+                    _ => None,
+                }),
+                _ => None,
+            });
+            self.test_scopes.push(TestScope {
+                label,
+                span: m.open_span.clone(),
+            });
+        }
         let scope_kind = match &m.kind {
             ModuleKind::Named(name) => ScopeKind::Module(name.value.clone()),
             ModuleKind::Test => ScopeKind::Test,
@@ -545,6 +577,22 @@ impl Compiler {
         }
         Ok(())
     }
+    /// Check if a test scope matches the test filter
+    ///
+    /// A test scope matches if any of its comments or binding names contain the filter
+    fn test_scope_matches(&self, items: &[Item]) -> bool {
+        let Some(filter) = &self.test_filter else {
+            return true;
+        };
+        items.iter().any(|item| match item {
+            Item::Words(lines) => lines.iter().flatten().any(|word| match &word.value {
+                Word::Comment(comment) => comment.contains(filter.as_str()),
+                _ => false,
+            }),
+            Item::Binding(binding) => binding.name.value.contains(filter.as_str()),
+            _ => false,
+        })
+    }
     pub(super) fn import(
         &mut self,
         import: crate::ast::Import,
@@ -37,10 +37,10 @@ use crate::{
     lsp::{CodeMeta, ImportSrc, SigDecl},
     optimize::{optimize_instrs, optimize_instrs_mut},
     parse::{count_placeholders, flip_unsplit_lines, parse, split_words},
-    Array, Assembly, BindingKind, Boxed, Diagnostic, DiagnosticKind, DocComment, DocCommentSig,
-    GitTarget, Ident, ImplPrimitive, InputSrc, IntoInputSrc, IntoSysBackend, Primitive, RunMode,
-    SemanticComment, SysBackend, Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value, CONSTANTS,
-    EXAMPLE_UA, SUBSCRIPT_NUMS, VERSION,
+    Array, Assembly, BindingKind, Boxed, Diagnostic, DiagnosticCategory, DiagnosticKind,
+    DocComment, DocCommentSig, GitTarget, Ident, ImplPrimitive, InputSrc, IntoInputSrc,
+    IntoSysBackend, Primitive, RunMode, SemanticComment, SysBackend, Uiua, UiuaError,
+    UiuaErrorKind, UiuaResult, Value, CONSTANTS, EXAMPLE_UA, SUBSCRIPT_NUMS, VERSION,
 };
 
 /// Wrap a block in a closure call to reduce stack size
@@ -72,6 +72,12 @@ pub struct Compiler {
     higher_scopes: Vec<Scope>,
     /// Determines which How test scopes are run
     mode: RunMode,
+    /// Only run test scopes matching this filter
+    test_filter: Option<String>,
+    /// The test scopes that were compiled
+    test_scopes: Vec<TestScope>,
+    /// The number of test scopes skipped by the test filter
+    skipped_test_scopes: usize,
     /// The paths of files currently being imported (used to detect import cycles)
     current_imports: Vec<PathBuf>,
     /// The bindings of imported files
@@ -98,6 +104,10 @@ pub struct Compiler {
     diagnostics: BTreeSet<Diagnostic>,
     /// Print diagnostics as they are encountered
     pub(crate) print_diagnostics: bool,
+    /// Kinds of diagnostics that are treated as errors
+    denied_diagnostics: HashSet<DiagnosticKind>,
+    /// Categories of diagnostics that are treated as errors
+    denied_categories: HashSet<DiagnosticCategory>,
     /// Whether to evaluate comptime code
     comptime: bool,
     /// The comptime mode
@@ -106,6 +116,15 @@ pub struct Compiler {
     macro_env: Uiua,
 }
 
+/// A test scope that was compiled
+#[derive(Debug, Clone)]
+pub struct TestScope {
+    /// The scope's label, taken from its first comment
+    pub label: Option<String>,
+    /// The span of the scope's opening delimiter
+    pub span: CodeSpan,
+}
+
 impl Default for Compiler {
     fn default() -> Self {
         Compiler {
@@ -117,6 +136,9 @@ impl Default for Compiler {
             scope: Scope::default(),
             higher_scopes: Vec::new(),
             mode: RunMode::All,
+            test_filter: None,
+            test_scopes: Vec::new(),
+            skipped_test_scopes: 0,
             current_imports: Vec::new(),
             imports: HashMap::new(),
             index_macros: HashMap::new(),
@@ -129,6 +151,8 @@ impl Default for Compiler {
             deprecated_prim_errors: HashSet::new(),
             diagnostics: BTreeSet::new(),
             print_diagnostics: false,
+            denied_diagnostics: HashSet::new(),
+            denied_categories: HashSet::new(),
             comptime: true,
             pre_eval_mode: PreEvalMode::default(),
             macro_env: Uiua::default(),
@@ -399,11 +423,41 @@ impl Compiler {
         self.print_diagnostics = print_diagnostics;
         self
     }
+    /// Treat diagnostics of the given kind as errors
+    ///
+    /// Denied diagnostics stop compilation instead of being printed or accumulated.
+    /// [`DiagnosticKind::Info`] cannot be denied.
+    pub fn deny_diagnostic(&mut self, kind: DiagnosticKind) -> &mut Self {
+        if kind != DiagnosticKind::Info {
+            self.denied_diagnostics.insert(kind);
+        }
+        self
+    }
+    /// Treat diagnostics of the given category as errors
+    ///
+    /// This also enables the checks for categories that are off by default.
+    pub fn deny_category(&mut self, category: DiagnosticCategory) -> &mut Self {
+        self.denied_categories.insert(category);
+        self
+    }
     /// Set the run mode
     pub fn mode(&mut self, mode: RunMode) -> &mut Self {
         self.mode = mode;
         self
     }
+    /// Only run test scopes whose comments or binding names contain the given filter
+    pub fn test_filter(&mut self, filter: impl Into<String>) -> &mut Self {
+        self.test_filter = Some(filter.into());
+        self
+    }
+    /// Get the number of test scopes that were compiled and skipped by the test filter
+    pub fn test_scope_counts(&self) -> (usize, usize) {
+        (self.test_scopes.len(), self.skipped_test_scopes)
+    }
+    /// Get the test scopes that were compiled
+    pub fn test_scopes(&self) -> &[TestScope] {
+        &self.test_scopes
+    }
     /// Get the backend
     pub fn backend(&self) -> Arc<dyn SysBackend> {
         self.macro_env.rt.backend.clone()
@@ -440,6 +494,20 @@ impl Compiler {
         let src = self.asm.inputs.add_src(src, input);
         self.load_impl(input, src)
     }
+    /// Get the signature of some code without running it
+    ///
+    /// The code is compiled in a copy of the compiler, so no bindings are kept
+    pub fn signature_of(&self, input: &str) -> UiuaResult<Signature> {
+        let mut comp = self.clone();
+        comp.pre_eval_mode(PreEvalMode::Lazy);
+        let top_slices_start = comp.asm.top_slices.len();
+        comp.load_str(input)?;
+        let instrs: Vec<Instr> = (comp.asm.top_slices[top_slices_start..].iter())
+            .flat_map(|&slice| comp.asm.instrs(slice).iter().cloned())
+            .collect();
+        instrs_signature(&instrs)
+            .map_err(|e| comp.fatal_error(Span::Builtin, format!("Cannot infer signature: {e}")))
+    }
     fn scopes(&self) -> impl Iterator<Item = &Scope> {
         once(&self.scope).chain(self.higher_scopes.iter().rev())
     }
@@ -468,6 +536,7 @@ impl Compiler {
         })
     }
     fn load_impl(&mut self, input: &str, src: InputSrc) -> UiuaResult<&mut Self> {
+        let bindings_start = self.asm.bindings.len();
         let instrs_start = self.asm.instrs.len();
         let top_slices_start = self.asm.top_slices.len();
         let (items, errors, diagnostics) = parse(input, src.clone(), &mut self.asm.inputs);
@@ -490,6 +559,9 @@ impl Compiler {
         }
 
         let res = self.catching_crash(input, |env| env.items(items, false));
+        if let Ok(Ok(())) = res {
+            self.check_unused_bindings(bindings_start);
+        }
 
         if self.print_diagnostics {
             for diagnostic in self.take_diagnostics() {
@@ -514,6 +586,29 @@ impl Compiler {
             _ => Err(UiuaError::from_multi(take(&mut self.errors))),
         }
     }
+    /// Emit diagnostics for top-level bindings created since `start` that are never referenced
+    fn check_unused_bindings(&mut self, start: usize) {
+        if !(self.denied_categories).contains(&DiagnosticCategory::UnusedBinding) {
+            return;
+        }
+        let used: HashSet<usize> = self.code_meta.global_references.values().copied().collect();
+        let unused: Vec<(Ident, CodeSpan)> = (self.scope.names.iter())
+            .filter(|(_, local)| local.index >= start && !used.contains(&local.index))
+            .filter_map(|(name, local)| {
+                let binding = self.asm.bindings.get(local.index)?;
+                matches!(binding.kind, BindingKind::Const(_) | BindingKind::Func(_))
+                    .then(|| (name.clone(), binding.span.clone()))
+            })
+            .collect();
+        for (name, span) in unused {
+            self.emit_categorized_diagnostic(
+                format!("`{name}` is never used"),
+                DiagnosticKind::Warning,
+                DiagnosticCategory::UnusedBinding,
+                span,
+            );
+        }
+    }
     fn catching_crash<T>(
         &mut self,
         input: impl fmt::Display,
@@ -668,7 +763,7 @@ code:
         }
         let in_test = self.scopes().any(|sc| sc.kind == ScopeKind::Test);
         let can_run = match self.mode {
-            RunMode::Normal => !in_test,
+            RunMode::Normal | RunMode::Bench => !in_test,
             RunMode::Test => in_test,
             RunMode::All => true,
         };
@@ -729,7 +824,7 @@ code:
                         if let Ok(height) = &mut self.scope.stack_height {
                             *height = (*height + sig.outputs).saturating_sub(sig.args);
                             // Compile test assert
-                            if self.mode != RunMode::Normal
+                            if !matches!(self.mode, RunMode::Normal | RunMode::Bench)
                                 && sig.outputs == 0
                                 && !self
                                     .scopes()
@@ -951,6 +1046,26 @@ code:
             (path, FileScopeKind::Source)
         };
         if !self.imports.contains_key(&path) {
+            let same_file = |p: &PathBuf| {
+                *p == path
+                    || (p.canonicalize().ok())
+                        .zip(path.canonicalize().ok())
+                        .is_some_and(|(a, b)| a == b)
+            };
+            if let Some(i) = self.current_imports.iter().position(same_file) {
+                let chain: Vec<_> = (self.current_imports[i..].iter())
+                    .chain(once(&path))
+                    .map(|p| p.display().to_string())
+                    .collect();
+                return Err(self.fatal_error(
+                    span.clone(),
+                    format!(
+                        "Cycle detected importing {}: {}",
+                        path.display(),
+                        chain.join(" -> ")
+                    ),
+                ));
+            }
             let bytes = self
                 .backend()
                 .file_read_all(&path)
@@ -961,16 +1076,22 @@ code:
                         Err(e)
                     }
                 })
-                .map_err(|e| self.fatal_error(span.clone(), e))?;
+                .map_err(|e| {
+                    let attempted = if path.is_absolute() {
+                        path.clone()
+                    } else {
+                        current_dir()
+                            .map(|dir| dir.join(&path))
+                            .unwrap_or_else(|_| path.clone())
+                    };
+                    self.fatal_error(
+                        span.clone(),
+                        format!("Failed to import {}: {e}", attempted.display()),
+                    )
+                })?;
             let input: EcoString = String::from_utf8(bytes)
                 .map_err(|e| self.fatal_error(span.clone(), format!("Failed to read file: {e}")))?
                 .into();
-            if self.current_imports.iter().any(|p| p == &path) {
-                return Err(self.fatal_error(
-                    span.clone(),
-                    format!("Cycle detected importing {}", path.to_string_lossy()),
-                ));
-            }
             let import = self.in_scope(ScopeKind::File(file_kind), |env| {
                 env.load_str_src(&input, &path).map(drop)
             })?;
@@ -988,6 +1109,9 @@ code:
         Ok(path)
     }
     /// Resolve a declared import path relative to the path of the file that is being executed
+    ///
+    /// The result is canonicalized relative to the current directory when possible,
+    /// so that the same file imported via different relative paths shares a cache entry.
     pub(crate) fn resolve_import_path(&self, path: &Path) -> PathBuf {
         let mut target = if let Some(parent) = self.current_imports.last().and_then(|p| p.parent())
         {
@@ -2407,8 +2531,29 @@ code:
         let inputs = self.asm.inputs.clone();
         self.emit_diagnostic_impl(Diagnostic::new(message.into(), span, kind, inputs));
     }
+    /// Construct and add a diagnostic that belongs to a category
+    pub(crate) fn emit_categorized_diagnostic(
+        &mut self,
+        message: impl Into<String>,
+        kind: DiagnosticKind,
+        category: DiagnosticCategory,
+        span: impl Into<Span>,
+    ) {
+        let inputs = self.asm.inputs.clone();
+        let diagnostic = Diagnostic::new(message.into(), span, kind, inputs);
+        self.emit_diagnostic_impl(diagnostic.with_category(category));
+    }
     fn emit_diagnostic_impl(&mut self, diagnostic: Diagnostic) {
-        if self.print_diagnostics {
+        let denied = self.denied_diagnostics.contains(&diagnostic.kind)
+            || (diagnostic.category).is_some_and(|cat| self.denied_categories.contains(&cat));
+        if denied {
+            let e = UiuaErrorKind::Run(
+                diagnostic.span.sp(diagnostic.message),
+                diagnostic.inputs.into(),
+            )
+            .into();
+            self.errors.push(e);
+        } else if self.print_diagnostics {
             println!("{}", diagnostic.report()); // Allow println
         } else {
             self.diagnostics.insert(diagnostic);
@@ -2627,6 +2772,11 @@ code:
             }
             .with_execution_limit(Duration::from_millis(40));
             match env.run_asm(asm) {
+                // Leave code that reports something to run normally so the report is shown
+                Ok(()) if !env.rt.reports.is_empty() => {
+                    cache.borrow_mut().insert(instrs, None);
+                    Ok(None)
+                }
                 Ok(()) => {
                     let stack = env.take_stack();
                     let res = if stack.iter().any(|v| {
@@ -2121,13 +2121,14 @@ impl Compiler {
         if !ignore_remaining && !ph_stack.is_empty() {
             let span = (ph_stack.first().unwrap().span.clone())
                 .merge(ph_stack.last().unwrap().span.clone());
-            self.emit_diagnostic(
+            self.emit_categorized_diagnostic(
                 format!(
                     "Macro operand stack has {} item{} left",
                     ph_stack.len(),
                     if ph_stack.len() == 1 { "" } else { "s" }
                 ),
                 DiagnosticKind::Warning,
+                DiagnosticCategory::StackResidue,
                 span,
             );
         }
@@ -1,6 +1,9 @@
-use std::{convert::Infallible, error::Error, fmt, io, mem::take, path::PathBuf, sync::Arc};
+use std::{
+    convert::Infallible, error::Error, fmt, io, mem::take, path::PathBuf, str::FromStr, sync::Arc,
+};
 
 use colored::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     function::FunctionId,
@@ -316,6 +319,73 @@ impl UiuaError {
         }
         report
     }
+    /// Get machine-readable messages for the error
+    ///
+    /// Each parse error and bundled error gets its own message.
+    pub fn json_messages(&self) -> Vec<JsonMessage> {
+        let kind = ReportKind::Error;
+        let mut messages = match &self.kind {
+            UiuaErrorKind::Parse(errors, _) => (errors.iter())
+                .map(|error| JsonMessage::new(kind, error.value.to_string(), error.span.clone()))
+                .collect(),
+            UiuaErrorKind::Run(error, _) => {
+                vec![JsonMessage::new(kind, &error.value, error.span.clone())]
+            }
+            UiuaErrorKind::Throw(message, span, _) => {
+                vec![JsonMessage::new(kind, message.to_string(), span.clone())]
+            }
+            UiuaErrorKind::Timeout(span, _) => vec![JsonMessage::new(
+                kind,
+                "Maximum execution time exceeded",
+                span.clone(),
+            )],
+            _ => vec![JsonMessage::new(kind, self.to_string(), Span::Builtin)],
+        };
+        messages.extend(self.multi.iter().flat_map(Self::json_messages));
+        messages
+    }
+}
+
+/// A machine-readable error or diagnostic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonMessage {
+    /// The path of the file the message refers to
+    pub file: Option<String>,
+    /// The line of the start of the span, starting at 1
+    pub line: Option<u16>,
+    /// The column of the start of the span, starting at 1
+    pub column: Option<u16>,
+    /// The length of the span in characters
+    pub length: Option<u32>,
+    /// One of `error`, `warning`, `advice`, `style`, or `info`
+    pub severity: String,
+    /// The message itself
+    pub message: String,
+}
+
+impl JsonMessage {
+    fn new(kind: ReportKind, message: impl Into<String>, span: impl Into<Span>) -> Self {
+        let mut json = JsonMessage {
+            file: None,
+            line: None,
+            column: None,
+            length: None,
+            severity: kind.str().to_lowercase(),
+            message: message.into(),
+        };
+        if let Span::Code(mut span) = span.into() {
+            while let InputSrc::Macro(inner) = span.src {
+                span = *inner;
+            }
+            if let InputSrc::File(path) = &span.src {
+                json.file = Some(path.display().to_string());
+            }
+            json.line = Some(span.start.line);
+            json.column = Some(span.start.col);
+            json.length = Some(span.end.char_pos.saturating_sub(span.start.char_pos));
+        }
+        json
+    }
 }
 
 /// A message to be displayed to the user that is not an error
@@ -327,6 +397,8 @@ pub struct Diagnostic {
     pub message: String,
     /// What kind of diagnostic this is
     pub kind: DiagnosticKind,
+    /// The category of the diagnostic, if it has one
+    pub category: Option<DiagnosticCategory>,
     /// The inputs of the program
     pub inputs: Inputs,
 }
@@ -364,6 +436,52 @@ pub enum DiagnosticKind {
     Warning,
 }
 
+/// Categories of diagnostics that can be denied individually
+///
+/// [`DiagnosticCategory::Shadowing`] and [`DiagnosticCategory::UnusedBinding`] are
+/// only checked when they are denied, because both are common in ordinary scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticCategory {
+    /// A binding reuses a name that is already bound in the same scope
+    Shadowing,
+    /// A binding is never referenced in the file that defines it
+    UnusedBinding,
+    /// Values are left over on a stack where they are discarded
+    StackResidue,
+}
+
+impl DiagnosticCategory {
+    /// The name of the category, as used on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiagnosticCategory::Shadowing => "shadowing",
+            DiagnosticCategory::UnusedBinding => "unused-binding",
+            DiagnosticCategory::StackResidue => "stack-residue",
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name().fmt(f)
+    }
+}
+
+impl FromStr for DiagnosticCategory {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shadowing" => Ok(DiagnosticCategory::Shadowing),
+            "unused-binding" => Ok(DiagnosticCategory::UnusedBinding),
+            "stack-residue" => Ok(DiagnosticCategory::StackResidue),
+            _ => Err(format!(
+                "unknown diagnostic category `{s}`, \
+                expected one of shadowing, unused-binding, or stack-residue"
+            )),
+        }
+    }
+}
+
 impl fmt::Display for Diagnostic {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.message.fmt(f)
@@ -382,9 +500,15 @@ impl Diagnostic {
             message,
             span: span.into(),
             kind,
+            category: None,
             inputs,
         }
     }
+    /// Set the category of the diagnostic
+    pub fn with_category(mut self, category: DiagnosticCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
     /// Get a rich-text report for the diagnostic
     pub fn report(&self) -> Report {
         Report::new_multi(
@@ -393,6 +517,14 @@ impl Diagnostic {
             [(&self.message, self.span.clone())],
         )
     }
+    /// Get a machine-readable message for the diagnostic
+    pub fn json_message(&self) -> JsonMessage {
+        JsonMessage::new(
+            ReportKind::Diagnostic(self.kind),
+            &self.message,
+            self.span.clone(),
+        )
+    }
 }
 
 /// Kinds of reports
@@ -39,6 +39,25 @@ impl ConfigValue for usize {
     }
 }
 
+/// Spacing inside the brackets of single-line arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayInnerSpacing {
+    /// No spaces, like `[1 2 3]`
+    None,
+    /// A single space, like `[ 1 2 3 ]`
+    Single,
+}
+
+impl ConfigValue for ArrayInnerSpacing {
+    fn from_value(value: &Value, env: &Uiua, requirement: &'static str) -> UiuaResult<Self> {
+        match value.as_string(env, requirement)?.as_str() {
+            "none" => Ok(ArrayInnerSpacing::None),
+            "single" => Ok(ArrayInnerSpacing::Single),
+            _ => Err(env.error(requirement)),
+        }
+    }
+}
+
 macro_rules! requirement {
     ($name:ident, bool) => {
         concat!(
@@ -54,6 +73,13 @@ macro_rules! requirement {
             "' expects a natural number"
         )
     };
+    ($name:ident, ArrayInnerSpacing) => {
+        concat!(
+            "Format config option '",
+            stringify!($name),
+            "' expects \"none\" or \"single\""
+        )
+    };
 }
 
 #[cfg(test)]
@@ -67,6 +93,9 @@ macro_rules! param_type {
     (CompactMultilineMode) => {
         r#"`"always"`, `"never"`, or `"auto"`"#
     };
+    (ArrayInnerSpacing) => {
+        r#"`"none"` or `"single"`"#
+    };
 }
 
 #[cfg(test)]
@@ -218,8 +247,16 @@ create_config!(
     (multiline_indent, usize, 2),
     /// Whether to align consecutive end-of-line comments
     (align_comments, bool, true),
+    /// Whether to align the arrows of consecutive single-line bindings
+    (align_bindings, bool, true),
+    /// Whether to write number exponents in a canonical form, like `1e¯3` for `1E-3`
+    (normalize_numbers, bool, false),
     /// Whether to indent item imports
     (indent_item_imports, bool, true),
+    /// The spacing inside the brackets of single-line arrays, either `"none"` for `[1 2 3]` or `"single"` for `[ 1 2 3 ]`. Multiline arrays are unaffected.
+    (array_inner_spacing, ArrayInnerSpacing, ArrayInnerSpacing::None),
+    /// The maximum number of consecutive blank lines to keep between items. `0` removes them entirely.
+    (max_blank_lines, usize, 1),
 );
 
 /// The source from which to populate the formatter configuration.
@@ -506,13 +543,26 @@ impl<'a> Formatter<'a> {
     }
     fn format_items(&mut self, items: &[Item], depth: usize) {
         let mut max_name_len = 0;
+        let mut blank_lines = 0;
+        let mut first = true;
         for (i, item) in items.iter().enumerate() {
-            if i > 0 || depth > 0 {
+            // Limit consecutive blank lines
+            if matches!(item, Item::Words(lines) if lines.iter().all(Vec::is_empty)) {
+                blank_lines += 1;
+                if blank_lines > self.config.max_blank_lines {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            if !first || depth > 0 {
                 self.newline(depth);
             }
             // Calculate max name length to align single-line bindings
             match item {
-                Item::Binding(binding) if !words_are_multiline(&binding.words) => {
+                Item::Binding(binding)
+                    if self.config.align_bindings && !words_are_multiline(&binding.words) =>
+                {
                     if max_name_len == 0 {
                         max_name_len = items[i..]
                             .iter()
@@ -529,6 +579,7 @@ impl<'a> Formatter<'a> {
             }
             // Format item
             self.format_item(item, max_name_len, depth);
+            first = false;
         }
         // Align end-of-line comments
         if self.config.align_comments && !self.end_of_line_comments.is_empty() {
@@ -872,7 +923,7 @@ impl<'a> Formatter<'a> {
         match &word.value {
             Word::Number(s, n) => {
                 let grid_str = n.grid_string(false);
-                let formatted = if !grid_str.contains('…')
+                let mut formatted = if !grid_str.contains('…')
                     && grid_str.chars().count() < s.trim_end_matches('i').chars().count()
                     && !["tau", "pi", "eta"].iter().any(|name| s.contains(name))
                     && !grid_str.contains("τ/")
@@ -904,6 +955,9 @@ impl<'a> Formatter<'a> {
                         format_frag(s).into_owned()
                     }
                 };
+                if self.config.normalize_numbers {
+                    formatted = formatted.replace('E', "e").replace("e-", "e¯");
+                }
                 if formatted.starts_with(|c: char| c.is_ascii_digit())
                     && (self
                         .output
@@ -1013,7 +1067,17 @@ impl<'a> Formatter<'a> {
                 let indent = self.config.multiline_indent * depth;
                 let allow_compact = start_indent <= indent + 2;
 
+                let inner_space = self.config.array_inner_spacing == ArrayInnerSpacing::Single
+                    && arr.lines.len() == 1
+                    && arr.lines[0].iter().any(|word| word.value.is_code())
+                    && !(arr.lines[0].iter()).any(|word| word_is_multiline(&word.value));
+                if inner_space && arr.signature.is_none() {
+                    self.output.push(' ');
+                }
                 self.format_multiline_words(&arr.lines, allow_compact, true, true, true, depth + 1);
+                if inner_space {
+                    self.output.push(' ');
+                }
                 if arr.boxes {
                     self.output.push('}');
                 } else {
@@ -1344,7 +1408,17 @@ impl<'a> Formatter<'a> {
         } else {
             self.config.multiline_indent * depth
         };
+        let mut blank_lines = 0;
         for (i, line) in lines.iter().enumerate() {
+            // Limit consecutive blank lines, but keep a trailing empty line
+            if line.is_empty() && i < lines.len() - 1 {
+                blank_lines += 1;
+                if blank_lines > self.config.max_blank_lines {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
             if i > 0 || (!allow_compact && allow_leading_space) {
                 if line.is_empty() {
                     if allow_trailing_newline || prevent_compact || i < lines.len() - 1 {
@@ -1566,3 +1640,37 @@ F₁
     let formatted = format_str(input, &FormatConfig::default()).unwrap().output;
     assert_eq!(formatted, output);
 }
+
+#[test]
+#[cfg(test)]
+fn formatter_normalize_numbers() {
+    let config = FormatConfig::default().with_normalize_numbers(true);
+    let formatted = format_str("1E-3\n", &config).unwrap().output;
+    assert_eq!(formatted, "1e¯3\n");
+}
+
+#[test]
+#[cfg(test)]
+fn formatter_max_blank_lines() {
+    let input = "1\n\n\n\n2\n";
+    let formatted = format_str(input, &FormatConfig::default()).unwrap().output;
+    assert_eq!(formatted, "1\n\n2\n");
+    let config = FormatConfig::default().with_max_blank_lines(2);
+    let formatted = format_str(input, &config).unwrap().output;
+    assert_eq!(formatted, "1\n\n\n2\n");
+    let config = FormatConfig::default().with_max_blank_lines(0);
+    let formatted = format_str(input, &config).unwrap().output;
+    assert_eq!(formatted, "1\n2\n");
+}
+
+#[test]
+#[cfg(test)]
+fn formatter_array_inner_spacing() {
+    let config = FormatConfig::default().with_array_inner_spacing(ArrayInnerSpacing::Single);
+    let formatted = format_str("[1 2 3] {1 2} []\n", &config).unwrap().output;
+    assert_eq!(formatted, "[ 1 2 3 ] { 1 2 } []\n");
+    let formatted = format_str("[ 1 2 3 ]\n", &FormatConfig::default())
+        .unwrap()
+        .output;
+    assert_eq!(formatted, "[1 2 3]\n");
+}
@@ -0,0 +1,97 @@
+//! JUnit XML test reports
+
+use crate::{Span, TestResult, TestScope};
+
+struct TestCase<'a> {
+    name: String,
+    failures: Vec<&'a str>,
+}
+
+/// Generate a JUnit XML report from test scopes and the results of their assertions
+///
+/// Each test scope becomes a `<testcase>`. It is named by its first comment, or by its position if it has none.
+pub fn junit_report(suite: &str, scopes: &[TestScope], results: &[TestResult]) -> String {
+    let mut cases: Vec<TestCase> = (scopes.iter().enumerate())
+        .map(|(i, scope)| TestCase {
+            name: (scope.label.clone()).unwrap_or_else(|| format!("test {}", i + 1)),
+            failures: Vec::new(),
+        })
+        .collect();
+    let mut top_level = TestCase {
+        name: "top level".into(),
+        failures: Vec::new(),
+    };
+    let mut has_top_level = false;
+    for result in results {
+        // Assertions belong to the last test scope that starts before them
+        let scope = match &result.span {
+            Span::Code(span) => scopes.iter().rposition(|scope| {
+                scope.span.src == span.src && scope.span.start.byte_pos <= span.start.byte_pos
+            }),
+            Span::Builtin => None,
+        };
+        let case = if let Some(i) = scope {
+            &mut cases[i]
+        } else {
+            has_top_level = true;
+            &mut top_level
+        };
+        if let Some(failure) = &result.failure {
+            case.failures.push(failure);
+        }
+    }
+    if has_top_level {
+        cases.push(top_level);
+    }
+
+    let tests = cases.len();
+    let failures = cases
+        .iter()
+        .filter(|case| !case.failures.is_empty())
+        .count();
+    let suite = escape(suite);
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    for case in cases {
+        let name = escape(&case.name);
+        if case.failures.is_empty() {
+            xml.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"{suite}\"/>\n"
+            ));
+            continue;
+        }
+        xml.push_str(&format!(
+            "    <testcase name=\"{name}\" classname=\"{suite}\">\n"
+        ));
+        xml.push_str(&format!(
+            "      <failure message=\"{}\">{}</failure>\n",
+            escape(case.failures[0]),
+            escape(&case.failures.join("\n"))
+        ));
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
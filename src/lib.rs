@@ -129,6 +129,7 @@ The `uiua` crate has the following noteable feature flags:
     - `audio_encode`: Enables audio encoding and decoding
 - `native_sys`: Enables the [`NativeSys`] backend. This is the default backend used by the interpreter.
 - `audio`: Enables audio features in the [`NativeSys`] backend.
+- `http`: Enables the `&httpg` system function for `http://` URLs
 - `https`: Enables the `&httpsw` system function
 - `invoke`: Enables the `&invk` system function
 - `trash`: Enables the `&ftr` system function
@@ -159,6 +160,8 @@ pub mod format;
 mod function;
 mod grid_fmt;
 mod instr;
+#[doc(hidden)]
+pub mod junit;
 mod lex;
 pub mod lsp;
 mod optimize;
@@ -304,6 +307,304 @@ mod tests {
         }
     }
 
+    #[test]
+    fn scan_lines() {
+        use super::*;
+        use parking_lot::Mutex;
+        use std::{any::Any, collections::VecDeque};
+
+        #[derive(Default)]
+        struct PipedStdin(Mutex<VecDeque<String>>);
+        impl SysBackend for PipedStdin {
+            fn any(&self) -> &dyn Any {
+                self
+            }
+            fn any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+            fn scan_line_stdin(&self) -> Result<Option<String>, String> {
+                Ok(self.0.lock().pop_front())
+            }
+        }
+
+        let stdin = PipedStdin(Mutex::new(
+            ["first", "second", "", "fourth"].map(String::from).into(),
+        ));
+        let mut env = Uiua::with_backend(stdin);
+        env.run_str("&scl &scl &sc").unwrap();
+        let [first, rest, after_eof] = env.take_stack().try_into().unwrap();
+        assert_eq!(first.as_string(&env, "").unwrap(), "first");
+        let rest: Vec<String> = (rest.rows())
+            .map(|row| row.as_string(&env, "").unwrap())
+            .collect();
+        assert_eq!(rest, ["second", "", "fourth"]);
+        assert_eq!(after_eof.shape().dims(), [0]);
+    }
+
+    #[test]
+    fn eval_str() {
+        use super::*;
+        let stack = Uiua::eval_str("+1 2 3").unwrap();
+        assert_eq!(stack, [Value::from(3), Value::from(3)]);
+        assert!(Uiua::eval_str("&fras \"foo.txt\"").is_err());
+    }
+
+    #[test]
+    fn ast_json_round_trip() {
+        use super::*;
+        let code = "F ← +1\n⊂⊃(⊢|↘1) [1 2 3]\n{\"a\" @b}\n# Experimental!";
+        let (items, errors, _) = parse(code, (), &mut Inputs::default());
+        assert!(errors.is_empty());
+        let json = serde_json::to_string(&items).unwrap();
+        let items: Vec<ast::Item> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&items).unwrap(), json);
+    }
+
+    #[test]
+    fn mem_sys() {
+        use super::*;
+        let mut env = Uiua::with_backend(
+            MemSys::new()
+                .with_file("data/a.txt", "hello")
+                .with_dir("out"),
+        );
+        env.run_str(
+            r#"
+&fwa "out/b.txt" ⊂:"!" &fras "data/a.txt"
+&frn "out/b.txt" "out/c.txt"
+&fde "data"
+&p ⧻&fld "out""#,
+        )
+        .unwrap();
+        let sys = env.downcast_backend::<MemSys>().unwrap();
+        assert_eq!(sys.file("out/c.txt").unwrap(), b"hello!");
+        assert_eq!(sys.file_paths(), ["out/c.txt"]);
+        assert_eq!(sys.take_stdout_string(), "1\n");
+    }
+
+    #[test]
+    fn error_stack() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        // The error must happen at runtime rather than during pre-evaluation
+        assert!(env.run_str("5 6\n⊢ ↘3 ⇡⌊⚂").is_err());
+        assert_eq!(env.take_error_stack(), [Value::from(6), Value::from(5)]);
+        assert!(env.take_stack().is_empty());
+    }
+
+    #[test]
+    fn interrupt() {
+        use super::*;
+        let mut env = Uiua::with_safe_sys();
+        let handle = env.interrupt_handle();
+        // An interrupt while idle does not affect the next run
+        handle.interrupt();
+        env.run_str("+ 1 2").unwrap();
+        // Keep interrupting, because interrupts made before the run starts are ignored
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let interrupter = std::thread::spawn({
+            let (handle, done) = (handle.clone(), done.clone());
+            move || {
+                while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    handle.interrupt();
+                }
+            }
+        });
+        let err = env.run_str("⍢(+1|1) 0").err().unwrap();
+        done.store(true, std::sync::atomic::Ordering::Relaxed);
+        interrupter.join().unwrap();
+        assert!(matches!(err.kind, UiuaErrorKind::Interrupted), "{err}");
+        env.run_str("+ 1 2").unwrap();
+        assert_eq!(env.pop_num().unwrap(), 3.0);
+    }
+
+    #[test]
+    #[cfg(feature = "hash")]
+    fn hash() {
+        use super::*;
+        let hex = |code: &str| -> String {
+            let digest = Uiua::eval_str(&format!("# Experimental!\n{code}")).unwrap();
+            (digest[0]
+                .as_bytes(&Uiua::with_safe_sys(), "")
+                .unwrap()
+                .iter())
+            .map(|b| format!("{b:02x}"))
+            .collect()
+        };
+        assert_eq!(
+            hex(r#"hash "md5" "abc""#),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            hex(r#"hash "sha1" "abc""#),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        let err = Uiua::eval_str("# Experimental!\nhash \"crc\" \"abc\"").unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("Unknown hash algorithm \"crc\""),
+            "{message}"
+        );
+        assert!(message.contains("md5, sha1, sha224, sha256, sha384, and sha512"));
+    }
+
+    #[test]
+    fn deny_diagnostic_categories() {
+        use super::*;
+        let denied = |category, code: &str| {
+            (Compiler::new().deny_category(category))
+                .load_str(code)
+                .is_err()
+        };
+        use DiagnosticCategory::*;
+        assert!(denied(Shadowing, "X ← 1\nX ← 2\nX"));
+        assert!(!denied(Shadowing, "X ← 1\nY ← 2\n+X Y"));
+        assert!(denied(UnusedBinding, "X ← 1\nY ← 2\nX"));
+        assert!(!denied(UnusedBinding, "X ← 1\nX"));
+        assert!(denied(StackResidue, "F ← +1.\nF 1"));
+        assert!(!denied(StackResidue, "F ← +1\nF 1"));
+        // Other categories and the default compiler are unaffected
+        assert!(!denied(UnusedBinding, "X ← 1\nX ← 2\nX"));
+        assert!(Compiler::new().load_str("F ← +1.\nF 1").is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn http_max_bytes() {
+        use super::*;
+        use std::{
+            io::{BufRead, BufReader, Write},
+            net::TcpListener,
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                // Read the request so the client does not see a reset connection
+                let mut reader = BufReader::new(&stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 0) && line != "\r\n" {
+                    line.clear();
+                }
+                let body = "x".repeat(1000);
+                let response = format!("HTTP/1.0 200 OK\r\n\r\n{body}");
+                _ = stream.write_all(response.as_bytes());
+            }
+        });
+        let get = |max_bytes| {
+            let mut env = Uiua::with_native_sys().with_http_max_bytes(max_bytes);
+            env.run_str(&format!("&httpg \"http://127.0.0.1:{port}/\""))?;
+            env.pop(1).map(|val| val.shape().clone())
+        };
+        assert_eq!(get(2000).unwrap(), [1000]);
+        let err = get(100).unwrap_err();
+        assert!(err.to_string().contains("maximum size of 100 bytes"));
+    }
+
+    #[test]
+    fn instr_timings() {
+        use super::*;
+        use std::time::{Duration, Instant};
+        let timings = |code: &str| {
+            let mut env = Uiua::with_native_sys().record_instr_timings(true);
+            let start = Instant::now();
+            env.run_str(code).unwrap();
+            (env.instr_timings(), start.elapsed())
+        };
+        // Nested primitives are not counted twice
+        let (times, elapsed) = timings("⍥(⍥(+⚂)100)100 0");
+        let total: Duration = times.iter().map(|(_, time)| *time).sum();
+        assert!(total <= elapsed);
+        // Timings from a thread are merged in when it is waited on
+        let (times, _) = timings("wait spawn(⍥(+⚂)1000) 0");
+        assert!(times.iter().any(|(prim, _)| *prim == Primitive::Add));
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn image_encode_quality() {
+        use super::*;
+        let encode = |format: &str| {
+            let mut env = Uiua::with_safe_sys();
+            env.run_str(&format!("⧻img {format} ÷198⊞+.⇡100")).unwrap();
+            (env.pop_nat().unwrap(), env.take_reports().len())
+        };
+        assert!(encode(r#"{"jpg" 10}"#).0 < encode(r#"{"jpg" 90}"#).0);
+        // A quality for another format is ignored with a warning
+        let (png_size, png_reports) = encode(r#"{"png" 50}"#);
+        assert_eq!(png_size, encode(r#""png""#).0);
+        assert_eq!(png_reports, 1);
+    }
+
+    #[test]
+    fn signature_of() {
+        use super::*;
+        let mut comp = Compiler::new();
+        comp.load_str("F ← +1").unwrap();
+        assert_eq!(comp.signature_of("+").unwrap(), (2, 1));
+        assert_eq!(comp.signature_of("F").unwrap(), (1, 1));
+        assert_eq!(comp.signature_of("⊃F¯").unwrap(), (1, 2));
+        // The code is not run
+        assert_eq!(comp.signature_of("&sl 1000").unwrap(), (0, 0));
+        // Bindings from the code are not kept
+        assert_eq!(comp.signature_of("G ← 5").unwrap(), (0, 0));
+        assert!(comp.clone().load_str("G").is_err());
+        assert!(comp.signature_of("Unknown").is_err());
+        assert!(comp.signature_of("(+").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn image_decode_channels() {
+        use super::*;
+        let shape = |code: &str| Uiua::eval_str(code).unwrap()[0].shape().clone();
+        let gray = r#"img "png" [0_1 1_0]"#;
+        assert_eq!(shape(&format!("◌°img {gray}")), [2, 2, 1]);
+        assert_eq!(shape(&format!("◌°img {{4 {gray}}}")), [2, 2, 4]);
+        assert_eq!(shape(&format!("◌°img {{\"png\" 3 {gray}}}")), [2, 2, 3]);
+        assert!(Uiua::eval_str(&format!("°img {{5 {gray}}}")).is_err());
+    }
+
+    #[test]
+    fn json_error_messages() {
+        use super::*;
+        let err = Uiua::with_safe_sys().run_str("1\n+ @a @b").err().unwrap();
+        let messages = err.json_messages();
+        assert_eq!(messages.len(), 1);
+        let message = &messages[0];
+        assert_eq!(message.severity, "error");
+        assert_eq!(
+            (message.line, message.column, message.length),
+            (Some(2), Some(1), Some(1))
+        );
+        assert!(message.file.is_none());
+    }
+
+    #[test]
+    fn tsv() {
+        use super::*;
+        let tsv = |code: &str| Uiua::eval_str(code).unwrap()[0].to_tsv();
+        assert_eq!(tsv("↯2_3⇡6").unwrap(), "0\t1\t2\n3\t4\t5");
+        assert_eq!(tsv("{\"a\" \"b\\tc\"}").unwrap(), "a\tb\\tc");
+        assert_eq!(tsv("\"hi\"").unwrap(), "hi");
+        assert!(tsv("↯2_2_2 0").is_none());
+    }
+
+    #[test]
+    fn uasm_version() {
+        use super::*;
+        let asm = Compiler::new().load_str("+ 1 2").unwrap().finish();
+        let uasm = asm.to_uasm();
+        let mut env = Uiua::with_safe_sys();
+        env.run_asm(Assembly::from_uasm(&uasm).unwrap()).unwrap();
+        assert_eq!(env.pop_num().unwrap(), 3.0);
+        let stale = uasm.replacen(VERSION, "0.0.0", 1);
+        assert!(Assembly::from_uasm(&stale).err().unwrap().contains("0.0.0"));
+        let (_, headerless) = uasm.split_once('\n').unwrap();
+        assert!(Assembly::from_uasm(headerless).is_err());
+    }
+
     #[test]
     fn lsp_spans() {
         use super::*;
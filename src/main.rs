@@ -2,8 +2,11 @@
 compile_error!("To compile the uiua interpreter binary, you must enable the `binary` feature flag");
 
 use std::{
-    env, fmt, fs,
-    io::{self, stderr, stdin, BufRead, Write},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    env,
+    ffi::OsStr,
+    fmt, fs,
+    io::{self, stderr, stdin, BufRead, Read, Write},
     path::{Path, PathBuf},
     process::{exit, Child, Command, Stdio},
     sync::{
@@ -21,16 +24,22 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use rustyline::{error::ReadlineError, DefaultEditor};
 use uiua::{
-    format::{format_file, format_str, FormatConfig, FormatConfigSource},
+    ast::{Import, Item},
+    format::{format, format_file, format_str, FormatConfig, FormatConfigSource},
+    ident_modifier_args, is_ident_char,
+    junit::junit_report,
     lsp::BindingDocsKind,
-    Assembly, Compiler, NativeSys, PreEvalMode, PrimClass, Primitive, RunMode, Signature, SpanKind,
-    Uiua, UiuaError, UiuaErrorKind, UiuaResult, Value,
+    parse, seed_random, set_buffered_output, Array, Assembly, BindingKind, Boxed, CodeSpan,
+    Compiler, DiagnosticCategory, DiagnosticKind, Inputs, NativeSys, PreEvalMode, PrimClass,
+    PrimDoc, PrimDocLine, Primitive, RunMode, SafeSys, Signature, Span, SpanKind, SysBackend, Uiua,
+    UiuaError, UiuaErrorKind, UiuaResult, Value, SUBSCRIPT_NUMS, UASM_HEADER,
 };
 
 static PRESSED_CTRL_C: AtomicBool = AtomicBool::new(false);
 static WATCH_CHILD: Lazy<Mutex<Option<Child>>> = Lazy::new(Default::default);
 
 fn fail<T>(e: UiuaError) -> T {
+    _ = NativeSys.flush_stdout();
     println!("{}", e.report());
     exit(1)
 }
@@ -63,7 +72,7 @@ fn main() {
     #[cfg(feature = "raw_mode")]
     rawrrr::save_term();
 
-    if cfg!(feature = "profile") {
+    if cfg!(feature = "profile") && env::args().len() <= 1 {
         uiua::profile::run_profile();
         return;
     }
@@ -74,8 +83,23 @@ fn main() {
         print_stack(&rt.take_stack(), true);
         return;
     }
+    // Respect the NO_COLOR convention
+    if ["NO_COLOR", "UIUA_NO_COLOR"]
+        .iter()
+        .any(|var| env::var_os(var).is_some_and(|val| !val.is_empty()))
+    {
+        colored::control::set_override(false);
+    }
+    // `uiua --version --verbose` is handled separately because clap handles `--version` itself
+    if let [a, b] = &env::args().skip(1).collect::<Vec<_>>()[..] {
+        let is_version = |s: &str| s == "--version" || s == "-V";
+        if (is_version(a) && b == "--verbose") || (a == "--verbose" && is_version(b)) {
+            print_version(true);
+            return;
+        }
+    }
     match App::try_parse() {
-        Ok(app) => match app {
+        Ok(app) => match ProjectConfig::load().apply(app) {
             App::Init => {
                 if let Ok(path) = working_file_path() {
                     eprintln!("File already exists: {}", path.display());
@@ -84,17 +108,31 @@ fn main() {
                 }
             }
             App::Fmt {
-                path,
+                paths,
+                glob,
                 formatter_options,
                 io,
+                check,
+                diff,
             } => {
-                let config = FormatConfig::from_source(
-                    formatter_options.format_config_source,
-                    path.as_deref(),
-                )
-                .unwrap_or_else(fail);
-
-                if io {
+                if check || diff {
+                    let mut paths = paths;
+                    if let Some(pattern) = glob {
+                        paths.extend(glob_uiua_files(&pattern));
+                    } else if paths.is_empty() {
+                        paths = uiua_files(".".as_ref()).unwrap_or_else(fail);
+                    }
+                    let formatted =
+                        check_paths(&paths, &formatter_options.format_config_source(), diff);
+                    if check && !formatted {
+                        exit(1);
+                    }
+                } else if io {
+                    let config = FormatConfig::from_source(
+                        formatter_options.format_config_source(),
+                        paths.first().map(PathBuf::as_path),
+                    )
+                    .unwrap_or_else(fail);
                     let mut buffer = String::new();
                     let mut code = String::new();
                     let stdin = stdin();
@@ -111,24 +149,70 @@ fn main() {
                     }
                     let formatted = format_str(&code, &config).unwrap_or_else(fail);
                     print!("{}", formatted.output);
-                } else if let Some(path) = path {
-                    format_single_file(path, &config).unwrap_or_else(fail);
+                } else if !paths.is_empty() || glob.is_some() {
+                    let mut paths = paths;
+                    if let Some(pattern) = glob {
+                        paths.extend(glob_uiua_files(&pattern));
+                    }
+                    if !format_paths(&paths, &formatter_options.format_config_source()) {
+                        exit(1);
+                    }
                 } else {
+                    let config =
+                        FormatConfig::from_source(formatter_options.format_config_source(), None)
+                            .unwrap_or_else(fail);
                     format_multi_files(&config).unwrap_or_else(fail);
                 }
             }
             App::Run {
                 path,
                 no_format,
+                format: _,
                 no_color,
+                json,
+                tsv,
+                output,
                 formatter_options,
+                diagnostic_options,
                 time_instrs,
+                time_instrs_json,
+                profile_output,
                 limit,
+                max_memory,
+                buffered_output,
+                dump_stack_on_error,
+                error_format,
+                preload,
+                seed,
                 mode,
+                iterations,
+                stack_size,
                 #[cfg(feature = "audio")]
                 audio_options,
+                #[cfg(feature = "http")]
+                preview_image,
+                args_file,
                 args,
             } => {
+                if no_color {
+                    colored::control::set_override(false);
+                }
+                #[cfg(feature = "http")]
+                if let Some(path) = preview_image {
+                    uiua::set_preview_image_path(path);
+                }
+                if buffered_output {
+                    set_buffered_output(true);
+                }
+                let json_errors = error_format == "json";
+                if profile_output.is_some() && !uiua::profile::start_recording() {
+                    eprintln!(
+                        "{} --profile-output has no effect because \
+                        the profile feature is not enabled",
+                        "Warning:".yellow().bold()
+                    );
+                }
+                let args = load_args_file(args_file, args);
                 let path = if let Some(path) = path {
                     path
                 } else {
@@ -142,51 +226,113 @@ fn main() {
                 };
                 #[cfg(feature = "audio")]
                 setup_audio(audio_options);
-                let mut rt = Uiua::with_native_sys()
-                    .with_file_path(&path)
-                    .with_args(args)
-                    .time_instrs(time_instrs)
-                    .maybe_with_execution_limit(limit.map(Duration::from_secs_f64));
-                if path.extension().is_some_and(|ext| ext == "uasm") {
-                    let uasm = match fs::read_to_string(&path) {
-                        Ok(json) => json,
-                        Err(e) => {
-                            eprintln!("Failed to read assembly: {e}");
-                            return;
+                with_stack_size(stack_size, move || {
+                    let new_rt = || {
+                        if let Some(seed) = seed {
+                            seed_random(seed);
                         }
-                    };
-                    let assembly = match Assembly::from_uasm(&uasm) {
-                        Ok(assembly) => assembly,
-                        Err(e) => {
-                            eprintln!("Failed to parse assembly: {e}");
-                            return;
+                        let mut rt = Uiua::with_native_sys()
+                            .with_file_path(&path)
+                            .with_args(args.clone())
+                            .time_instrs(time_instrs)
+                            .record_instr_timings(time_instrs_json.is_some())
+                            .maybe_with_execution_limit(limit.map(Duration::from_secs_f64));
+                        if let Some(max_memory) = max_memory {
+                            rt = rt.with_max_memory(max_memory.saturating_mul(1024 * 1024));
                         }
+                        rt
                     };
-                    rt.run_asm(assembly).unwrap_or_else(fail);
-                } else {
-                    if !no_format {
-                        let config = FormatConfig::from_source(
-                            formatter_options.format_config_source,
-                            Some(&path),
-                        )
-                        .unwrap_or_else(fail);
-                        format_file(&path, &config).unwrap_or_else(fail);
+                    let mut rt = new_rt();
+                    // Bench mode records timings across every run
+                    let mut bench_timings = None;
+                    if is_assembly(&path) {
+                        let uasm = match fs::read_to_string(&path) {
+                            Ok(json) => json,
+                            Err(e) => {
+                                eprintln!("Failed to read assembly: {e}");
+                                return;
+                            }
+                        };
+                        let assembly = match Assembly::from_uasm(&uasm) {
+                            Ok(assembly) => assembly,
+                            Err(e) => {
+                                eprintln!("Failed to parse assembly: {e}");
+                                return;
+                            }
+                        };
+                        let res = rt.run_asm(assembly);
+                        _ = NativeSys.flush_stdout();
+                        if let Err(e) = res {
+                            if dump_stack_on_error {
+                                print_error_stack(&mut rt, !no_color);
+                            }
+                            print_error(&e, json_errors);
+                            exit(1);
+                        }
+                    } else {
+                        if !no_format {
+                            let config = FormatConfig::from_source(
+                                formatter_options.format_config_source(),
+                                Some(&path),
+                            )
+                            .unwrap_or_else(fail);
+                            format_file(&path, &config).unwrap_or_else(fail);
+                        }
+                        let mode = mode.unwrap_or(RunMode::Normal);
+                        let mut comp = Compiler::with_backend(NativeSys);
+                        diagnostic_options
+                            .apply(&mut comp)
+                            .mode(mode)
+                            .print_diagnostics(!json_errors);
+                        let mut compiled = false;
+                        let res = preload_files(&mut rt, &mut comp, &preload).and_then(|()| {
+                            comp.load_file(&path)?;
+                            compiled = true;
+                            if mode == RunMode::Bench {
+                                let timings = bench(&mut rt, &comp.finish(), iterations, new_rt)?;
+                                bench_timings = Some(timings);
+                                Ok(())
+                            } else {
+                                rt.run_compiler(&mut comp)
+                            }
+                        });
+                        _ = NativeSys.flush_stdout();
+                        if json_errors {
+                            print_json_diagnostics(&mut comp);
+                        }
+                        if let Err(e) = &res {
+                            if dump_stack_on_error && compiled {
+                                print_error_stack(&mut rt, !no_color);
+                            }
+                            print_error(e, json_errors);
+                        }
+                        rt.print_reports();
+                        if res.is_err() {
+                            exit(1);
+                        }
                     }
-                    let mode = mode.unwrap_or(RunMode::Normal);
-                    let res = rt.compile_run(|comp| {
-                        comp.mode(mode).print_diagnostics(true).load_file(&path)
-                    });
-                    if let Err(e) = &res {
-                        println!("{}", e.report());
+                    if let Some(path) = &time_instrs_json {
+                        let timings = bench_timings.unwrap_or_else(|| rt.instr_timings());
+                        write_instr_timings(timings, path);
                     }
-                    rt.print_reports();
-                    if res.is_err() {
-                        exit(1);
+                    if let Some(path) = &profile_output {
+                        if let Err(e) = uiua::profile::write_folded_stacks(path) {
+                            eprintln!("Failed to write profile: {e}");
+                        }
                     }
-                }
-                print_stack(&rt.take_stack(), !no_color);
-                #[cfg(feature = "raw_mode")]
-                rawrrr::disable_raw();
+                    let stack = rt.take_stack();
+                    if let Some(output) = &output {
+                        write_stack(stack, &rt, json, tsv, output);
+                    } else if json {
+                        print_stack_json(stack, &rt);
+                    } else if tsv {
+                        print_stack_tsv(&stack);
+                    } else {
+                        print_stack(&stack, !no_color);
+                    }
+                    #[cfg(feature = "raw_mode")]
+                    rawrrr::disable_raw();
+                });
             }
             App::Build { path, output } => {
                 let path = if let Some(path) = path {
@@ -216,26 +362,63 @@ fn main() {
             App::Eval {
                 code,
                 no_color,
+                buffered_output,
+                preload,
+                seed,
+                stack_size,
                 #[cfg(feature = "audio")]
                 audio_options,
+                args_file,
                 args,
             } => {
+                if no_color {
+                    colored::control::set_override(false);
+                }
+                if buffered_output {
+                    set_buffered_output(true);
+                }
+                let code = if code == "-" {
+                    let mut code = String::new();
+                    if let Err(e) = stdin().read_to_string(&mut code) {
+                        eprintln!("Failed to read code from stdin: {e}");
+                        exit(1);
+                    }
+                    code
+                } else {
+                    code
+                };
+                let args = load_args_file(args_file, args);
                 #[cfg(feature = "audio")]
                 setup_audio(audio_options);
-                let mut rt = Uiua::with_native_sys().with_args(args);
-                rt.compile_run(|comp| {
-                    comp.mode(RunMode::Normal)
-                        .print_diagnostics(true)
-                        .load_str(&code)
-                })
-                .unwrap_or_else(fail);
-                print_stack(&rt.take_stack(), !no_color);
+                with_stack_size(stack_size, move || {
+                    if let Some(seed) = seed {
+                        seed_random(seed);
+                    }
+                    let mut rt = Uiua::with_native_sys().with_args(args);
+                    let mut comp = Compiler::with_backend(NativeSys);
+                    comp.mode(RunMode::Normal).print_diagnostics(true);
+                    preload_files(&mut rt, &mut comp, &preload).unwrap_or_else(fail);
+                    comp.load_str(&code).unwrap_or_else(fail);
+                    rt.run_compiler(&mut comp).unwrap_or_else(fail);
+                    _ = NativeSys.flush_stdout();
+                    print_stack(&rt.take_stack(), !no_color);
+                });
             }
             App::Test {
                 path,
                 formatter_options,
+                diagnostic_options,
+                seed,
+                filter,
+                junit,
+                error_format,
+                args_file,
                 args,
             } => {
+                if let Some(seed) = seed {
+                    seed_random(seed);
+                }
+                let args = load_args_file(args_file, args);
                 let path = if let Some(path) = path {
                     path
                 } else {
@@ -247,42 +430,100 @@ fn main() {
                         }
                     }
                 };
-                let config =
-                    FormatConfig::from_source(formatter_options.format_config_source, Some(&path))
-                        .unwrap_or_else(fail);
+                let config = FormatConfig::from_source(
+                    formatter_options.format_config_source(),
+                    Some(&path),
+                )
+                .unwrap_or_else(fail);
                 format_file(&path, &config).unwrap_or_else(fail);
                 let mut rt = Uiua::with_native_sys()
                     .with_file_path(&path)
                     .with_args(args);
+                let json_errors = error_format == "json";
+                let mut test_scopes = (0, 0);
+                let mut scopes = Vec::new();
                 let res = rt.compile_run(|comp| {
-                    comp.mode(RunMode::Test)
-                        .print_diagnostics(true)
-                        .load_file(path)
+                    let comp = diagnostic_options
+                        .apply(comp)
+                        .mode(RunMode::Test)
+                        .print_diagnostics(!json_errors);
+                    if let Some(filter) = &filter {
+                        comp.test_filter(filter);
+                    }
+                    let comp = comp.load_file(&path)?;
+                    if json_errors {
+                        print_json_diagnostics(comp);
+                    }
+                    test_scopes = comp.test_scope_counts();
+                    scopes = comp.test_scopes().to_vec();
+                    Ok(comp)
                 });
                 if let Err(e) = &res {
-                    println!("{}", e.report());
+                    print_error(e, json_errors);
                 }
                 rt.print_reports();
+                if filter.is_some() {
+                    let (run, skipped) = test_scopes;
+                    println!(
+                        "{run} test scope{} run, {skipped} skipped",
+                        if run == 1 { "" } else { "s" }
+                    );
+                }
+                if let Some(junit_path) = junit {
+                    let suite = path.to_string_lossy();
+                    let report = junit_report(&suite, &scopes, &rt.take_test_results());
+                    if let Err(e) = fs::write(&junit_path, report) {
+                        eprintln!("Failed to write {}: {e}", junit_path.display());
+                        exit(1);
+                    }
+                }
                 if res.is_err() {
                     exit(1);
                 }
             }
             App::Watch {
                 no_format,
+                format: _,
                 no_color,
                 formatter_options,
                 clear,
+                args_file,
                 args,
                 stdin_file,
+                stack_size,
+                watch_dir,
+                no_recursive,
+                debounce,
+                #[cfg(feature = "http")]
+                serve,
+                once,
             } => {
+                if no_color {
+                    colored::control::set_override(false);
+                }
+                let initial_path = match working_file_path() {
+                    Ok(path) => Some(path),
+                    Err(e @ NoWorkingFile::MissingMain(_)) => {
+                        eprintln!("{e}");
+                        return;
+                    }
+                    Err(_) => None,
+                };
                 if let Err(e) = (WatchArgs {
-                    initial_path: working_file_path().ok(),
+                    initial_path,
                     format: !no_format,
                     color: !no_color,
-                    format_config_source: formatter_options.format_config_source,
+                    format_config_source: formatter_options.format_config_source(),
                     clear,
-                    args,
+                    args: load_args_file(args_file, args),
                     stdin_file,
+                    stack_size,
+                    watch_dir: watch_dir.unwrap_or_else(|| PathBuf::from(".")),
+                    recursive: !no_recursive,
+                    debounce: Duration::from_millis(debounce),
+                    #[cfg(feature = "http")]
+                    serve,
+                    once,
                 })
                 .watch()
                 {
@@ -296,12 +537,21 @@ fn main() {
                 formatter_options,
                 #[cfg(feature = "audio")]
                 audio_options,
-                stack,
+                clear_stack,
+                stack: _,
+                preload,
+                session,
+                seed,
+                args_file,
                 args,
             } => {
+                if let Some(seed) = seed {
+                    seed_random(seed);
+                }
+                let args = load_args_file(args_file, args);
                 let config = FormatConfig {
                     trailing_newline: false,
-                    ..FormatConfig::from_source(formatter_options.format_config_source, None)
+                    ..FormatConfig::from_source(formatter_options.format_config_source(), None)
                         .unwrap_or_else(fail)
                 };
 
@@ -310,11 +560,12 @@ fn main() {
                 let mut rt = Uiua::with_native_sys().with_args(args);
                 let mut compiler = Compiler::with_backend(NativeSys);
                 compiler.mode(RunMode::Normal).print_diagnostics(true);
+                preload_files(&mut rt, &mut compiler, &preload).unwrap_or_else(fail);
                 if let Some(file) = file {
                     compiler.load_file(file).unwrap_or_else(fail);
                     rt.run_compiler(&mut compiler).unwrap_or_else(fail);
                 }
-                repl(rt, compiler, true, stack, config);
+                repl(rt, compiler, true, clear_stack, config, session);
             }
             App::Update { main, check } => update(main, check),
             App::Module { command } => {
@@ -388,7 +639,119 @@ fn main() {
                     }
                 }
             }
+            App::Deps { path, tree } => {
+                let path = if let Some(path) = path {
+                    path
+                } else {
+                    match working_file_path() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            return;
+                        }
+                    }
+                };
+                let graph = ImportGraph::load(&path).unwrap_or_else(fail);
+                if tree {
+                    graph.print_tree();
+                } else {
+                    for path in graph.dependencies() {
+                        println!("{}", path.display());
+                    }
+                }
+                if !graph.cycles.is_empty() {
+                    for cycle in &graph.cycles {
+                        let chain: Vec<_> = cycle.iter().map(|p| p.display().to_string()).collect();
+                        eprintln!("Import cycle detected: {}", chain.join(" -> "));
+                    }
+                    exit(1);
+                }
+            }
+            App::Graph { path, output } => {
+                let path = if let Some(path) = path {
+                    path
+                } else {
+                    match working_file_path() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            return;
+                        }
+                    }
+                };
+                let dot = ImportGraph::load(&path).unwrap_or_else(fail).to_dot();
+                if let Some(output) = output {
+                    if let Err(e) = fs::write(&output, dot) {
+                        eprintln!("Failed to write {}: {e}", output.display());
+                        exit(1);
+                    }
+                } else {
+                    print!("{dot}");
+                }
+            }
+            App::Bundle { path, output } => {
+                let path = if let Some(path) = path {
+                    path
+                } else {
+                    match working_file_path() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            return;
+                        }
+                    }
+                };
+                let bundled = bundle(&path).unwrap_or_else(fail);
+                if let Some(output) = output {
+                    if let Err(e) = fs::write(&output, bundled) {
+                        eprintln!("Failed to write {}: {e}", output.display());
+                        exit(1);
+                    }
+                } else {
+                    print!("{bundled}");
+                }
+            }
+            App::Ast { path, json } => {
+                let path = if let Some(path) = path {
+                    path
+                } else {
+                    match working_file_path() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            eprintln!("{}", e);
+                            return;
+                        }
+                    }
+                };
+                let input = fs::read_to_string(&path)
+                    .map_err(|e| UiuaError::load(path.clone(), e))
+                    .unwrap_or_else(fail);
+                let mut inputs = Inputs::default();
+                let (items, errors, _) = parse(&input, &path, &mut inputs);
+                if !errors.is_empty() {
+                    fail::<()>(UiuaErrorKind::Parse(errors, inputs.into()).into());
+                }
+                if json {
+                    match serde_json::to_string_pretty(&items) {
+                        Ok(json) => println!("{json}"),
+                        Err(e) => {
+                            eprintln!("Failed to serialize syntax tree: {e}");
+                            exit(1);
+                        }
+                    }
+                } else {
+                    println!("{items:#?}");
+                }
+            }
+            App::Doc { name } => print_prim_doc(&name),
+            App::Glyphs { format } => print_glyphs(&format),
             App::Find { path, text, raw } => find(path, text, raw).unwrap_or_else(fail),
+            App::Version { verbose } => print_version(verbose),
+            App::SelfTest => {
+                if !self_test() {
+                    exit(1);
+                }
+            }
         },
         Err(e)
             if e.kind() == ErrorKind::InvalidSubcommand
@@ -430,6 +793,8 @@ fn main() {
 enum NoWorkingFile {
     NoFile,
     MultipleFiles,
+    /// `UIUA_MAIN` names a file that does not exist
+    MissingMain(PathBuf),
 }
 
 impl fmt::Display for NoWorkingFile {
@@ -444,12 +809,27 @@ impl fmt::Display for NoWorkingFile {
                 .ua files found. Please specify which file to run \
                 with `uiua run <PATH>`"
             }
+            NoWorkingFile::MissingMain(path) => {
+                return write!(
+                    f,
+                    "UIUA_MAIN is set to {}, but that file does not exist",
+                    path.display()
+                )
+            }
         }
         .fmt(f)
     }
 }
 
 fn working_file_path() -> Result<PathBuf, NoWorkingFile> {
+    if let Some(main) = env::var_os("UIUA_MAIN").filter(|main| !main.is_empty()) {
+        let main = PathBuf::from(main);
+        return if main.exists() {
+            Ok(main)
+        } else {
+            Err(NoWorkingFile::MissingMain(main))
+        };
+    }
     let main_in_src = PathBuf::from("src/main.ua");
     let main = if main_in_src.exists() {
         main_in_src
@@ -483,6 +863,104 @@ struct WatchArgs {
     clear: bool,
     args: Vec<String>,
     stdin_file: Option<PathBuf>,
+    stack_size: Option<usize>,
+    watch_dir: PathBuf,
+    recursive: bool,
+    debounce: Duration,
+    #[cfg(feature = "http")]
+    serve: Option<u16>,
+    once: bool,
+}
+
+const DEFAULT_DEBOUNCE_MS: u64 = 150;
+
+/// The page served by `uiua watch --serve`
+///
+/// It polls `/version` and reloads the image when it changes.
+#[cfg(feature = "http")]
+const PREVIEW_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>Uiua Preview</title>
+</head>
+<body style="margin: 0; height: 100vh; display: flex; align-items: center; justify-content: center; background: #222;">
+  <img id="image" alt="No image has been shown yet" style="max-width: 100%; max-height: 100%; color: #aaa;">
+  <script>
+    let version = "";
+    setInterval(async () => {
+      try {
+        const latest = await (await fetch("/version")).text();
+        if (latest !== version) {
+          version = latest;
+          document.getElementById("image").src = "/image.png?v=" + latest;
+        }
+      } catch {}
+    }, 500);
+  </script>
+</body>
+</html>
+"#;
+
+/// Serve the latest image shown by the watched program over HTTP
+#[cfg(feature = "http")]
+fn serve_preview(port: u16, image_path: PathBuf) -> io::Result<()> {
+    let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+    println!("Serving image preview at http://localhost:{port}");
+    std::thread::spawn(move || {
+        for stream in listener.incoming().filter_map(Result::ok) {
+            _ = handle_preview_request(stream, &image_path);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+fn handle_preview_request(mut stream: std::net::TcpStream, image_path: &Path) -> io::Result<()> {
+    let mut reader = io::BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let route = target.split('?').next().unwrap_or(target);
+    let not_found = |message: &str| ("404 Not Found", "text/plain", message.as_bytes().to_vec());
+    let (status, content_type, body) = match route {
+        "/" => ("200 OK", "text/html", PREVIEW_PAGE.as_bytes().to_vec()),
+        "/image.png" => match fs::read(image_path) {
+            Ok(bytes) => ("200 OK", "image/png", bytes),
+            Err(_) => not_found("No image has been shown yet"),
+        },
+        "/version" => {
+            let modified = fs::metadata(image_path).and_then(|meta| meta.modified());
+            let version = modified
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |dur| dur.as_millis());
+            ("200 OK", "text/plain", version.to_string().into_bytes())
+        }
+        _ => not_found("Not found"),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: {content_type}\r\n\
+        Content-Length: {}\r\n\
+        Cache-Control: no-store\r\n\
+        Connection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)
+}
+
+/// Whether a path looks like a temporary file created by an editor
+fn is_editor_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with('~') || name.starts_with(".#"))
 }
 
 impl Default for WatchArgs {
@@ -495,6 +973,13 @@ impl Default for WatchArgs {
             clear: false,
             args: Vec::new(),
             stdin_file: None,
+            stack_size: None,
+            watch_dir: PathBuf::from("."),
+            recursive: true,
+            debounce: Duration::from_millis(DEFAULT_DEBOUNCE_MS),
+            #[cfg(feature = "http")]
+            serve: None,
+            once: false,
         }
     }
 }
@@ -509,14 +994,40 @@ impl WatchArgs {
             clear,
             args,
             stdin_file,
+            stack_size,
+            watch_dir,
+            recursive,
+            debounce,
+            #[cfg(feature = "http")]
+            serve,
+            once,
         } = self;
         let (send, recv) = channel();
         let mut watcher = notify::recommended_watcher(send).unwrap();
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
         watcher
-            .watch(Path::new("."), RecursiveMode::Recursive)
+            .watch(&watch_dir, mode)
             .unwrap_or_else(|e| panic!("Failed to watch directory: {e}"));
 
-        println!("Watching for changes... (end with ctrl+C, use `uiua help` to see options)");
+        #[cfg(feature = "http")]
+        let preview_image = if let Some(port) = serve {
+            let path = env::temp_dir().join(format!("uiua-preview-{}.png", std::process::id()));
+            _ = fs::remove_file(&path);
+            serve_preview(port, path.clone())?;
+            Some(path)
+        } else {
+            None
+        };
+        #[cfg(not(feature = "http"))]
+        let preview_image: Option<PathBuf> = None;
+
+        if !once {
+            println!("Watching for changes... (end with ctrl+C, use `uiua help` to see options)");
+        }
 
         let config = FormatConfig::from_source(format_config_source, initial_path.as_deref()).ok();
         #[cfg(feature = "audio")]
@@ -553,12 +1064,17 @@ impl WatchArgs {
                 };
                 match formatted {
                     Ok(input) => {
-                        if input.is_empty() {
+                        if once {
+                            if input.is_empty() {
+                                return Ok(());
+                            }
+                        } else {
                             clear_watching();
-                            print_watching();
-                            return Ok(());
+                            if input.is_empty() {
+                                print_watching();
+                                return Ok(());
+                            }
                         }
-                        clear_watching();
                         #[cfg(feature = "audio")]
                         let audio_time =
                             f64::from_bits(audio_time_clone.load(Ordering::Relaxed)).to_string();
@@ -572,6 +1088,9 @@ impl WatchArgs {
                                 .arg("run")
                                 .arg(path)
                                 .args((!color).then_some("--no-color"))
+                                .args(stack_size.into_iter().flat_map(|size| {
+                                    ["--stack-size".to_string(), size.to_string()]
+                                }))
                                 .args([
                                     "--no-format",
                                     "--mode",
@@ -586,6 +1105,9 @@ impl WatchArgs {
                                     &audio_port,
                                 ])
                                 .args(&args)
+                                .args(preview_image.iter().flat_map(|path| {
+                                    [OsStr::new("--preview-image"), path.as_os_str()]
+                                }))
                                 .stdin(stdin_file.map_or_else(Stdio::inherit, Into::into))
                                 .spawn()
                                 .unwrap(),
@@ -595,6 +1117,9 @@ impl WatchArgs {
                     Err(e) => {
                         if let UiuaErrorKind::Format(..) = e.kind {
                             sleep(Duration::from_millis((i as u64 + 1) * 10))
+                        } else if once {
+                            eprintln!("{}", e.report());
+                            return Ok(());
                         } else {
                             clear_watching();
                             println!("{}", e.report());
@@ -607,10 +1132,25 @@ impl WatchArgs {
             println!("Failed to format file after {TRIES} tries");
             Ok(())
         };
+        if once {
+            let Some(path) = initial_path else {
+                return Err(io::Error::new(io::ErrorKind::NotFound, "No file to run"));
+            };
+            run(&path, stdin_file.as_ref())?;
+            let child = WATCH_CHILD.lock().take();
+            let code = match child {
+                Some(mut child) => child.wait()?.code().unwrap_or(1),
+                // An empty file has nothing to run
+                None if fs::metadata(&path).is_ok_and(|meta| meta.len() == 0) => 0,
+                None => 1,
+            };
+            exit(code);
+        }
         if let Some(path) = initial_path {
             run(&path, stdin_file.as_ref())?;
         }
-        let mut last_time = Instant::now();
+        // The most recently changed file and when it changed
+        let mut pending: Option<(PathBuf, Instant)> = None;
         loop {
             sleep(Duration::from_millis(10));
             if let Some(path) = recv
@@ -619,19 +1159,22 @@ impl WatchArgs {
                 .filter(|event| matches!(event.kind, EventKind::Modify(_)))
                 .flat_map(|event| event.paths)
                 .filter(|path| path.extension().map_or(false, |ext| ext == "ua"))
+                .filter(|path| !is_editor_temp_file(path))
                 .last()
             {
-                if last_time.elapsed() > Duration::from_millis(100) {
-                    if clear {
-                        if cfg!(target_os = "windows") {
-                            _ = Command::new("cmd").args(["/C", "cls"]).status();
-                        } else {
-                            _ = Command::new("clear").status();
-                        }
+                pending = Some((path, Instant::now()));
+            }
+            // Only run once the filesystem has been quiet for the debounce period
+            if (pending.as_ref()).is_some_and(|(_, time)| time.elapsed() >= debounce) {
+                let (path, _) = pending.take().unwrap();
+                if clear {
+                    if cfg!(target_os = "windows") {
+                        _ = Command::new("cmd").args(["/C", "cls"]).status();
+                    } else {
+                        _ = Command::new("clear").status();
                     }
-                    run(&path, stdin_file.as_ref())?;
-                    last_time = Instant::now();
                 }
+                run(&path, stdin_file.as_ref())?;
             }
             let mut child = WATCH_CHILD.lock();
             if let Some(ch) = &mut *child {
@@ -662,23 +1205,110 @@ enum App {
         path: Option<PathBuf>,
         #[clap(long, help = "Don't format the file before running")]
         no_format: bool,
-        #[clap(long, help = "Don't colorize stack output")]
+        #[clap(
+            long,
+            conflicts_with = "no_format",
+            help = "Format the file before running, even if uiua.toml sets no-format"
+        )]
+        format: bool,
+        #[clap(long, help = "Don't colorize output")]
         no_color: bool,
+        #[clap(long, help = "Print the final stack as a JSON array")]
+        json: bool,
+        #[clap(
+            long,
+            conflicts_with = "json",
+            help = "Print each value on the final stack as tab-separated values. \
+                    Rank-2 arrays print one line per row."
+        )]
+        tsv: bool,
+        #[clap(
+            short,
+            long,
+            help = "Write the final stack to a file instead of stdout"
+        )]
+        output: Option<PathBuf>,
         #[clap(flatten)]
         formatter_options: FormatterOptions,
+        #[clap(flatten)]
+        diagnostic_options: DiagnosticOptions,
         #[clap(long, help = "Emit the duration of each instruction's execution")]
         time_instrs: bool,
-        #[clap(long, short = 'l', help = "Set an execution limit in seconds")]
+        #[clap(
+            long,
+            value_name = "PATH",
+            help = "Write the total execution time of each primitive to a JSON file. \
+                    Time spent in other primitives a primitive calls is not included. \
+                    In bench mode, times are summed over all runs."
+        )]
+        time_instrs_json: Option<PathBuf>,
+        #[clap(
+            long,
+            value_name = "PATH",
+            help = "Write a profile of the interpreter in the folded stack format \
+                    used by flamegraph tools. Requires the profile feature."
+        )]
+        profile_output: Option<PathBuf>,
+        #[clap(
+            long,
+            short = 'l',
+            visible_alias = "timeout",
+            help = "Set an execution limit in seconds. \
+                    The limit is checked between instructions, so it is not preemptive."
+        )]
         limit: Option<f64>,
+        #[clap(
+            long,
+            value_name = "MB",
+            help = "Set the maximum size of each array in megabytes. \
+                    The limit applies to arrays separately, not to their total, \
+                    and it can only lower the built-in limit of 4096 MB."
+        )]
+        max_memory: Option<usize>,
+        #[clap(long, help = "Buffer stdout until it is flushed or the program ends")]
+        buffered_output: bool,
+        #[clap(long, help = "Print the stack before the error if the program fails")]
+        dump_stack_on_error: bool,
+        #[clap(
+            long,
+            default_value = "human",
+            value_parser = ["human", "json"],
+            help = "How to print errors and diagnostics. \
+                    json prints one JSON object per message to stderr."
+        )]
+        error_format: String,
+        #[clap(
+            long,
+            value_name = "PATH",
+            help = "Run a file first so that its bindings are in scope. Can be repeated"
+        )]
+        preload: Vec<PathBuf>,
+        #[clap(long, help = "Seed the random number generator")]
+        seed: Option<u64>,
         #[clap(long, help = "Run the file in a specific mode")]
         mode: Option<RunMode>,
+        #[clap(
+            long,
+            default_value_t = 100,
+            help = "The number of times to run the file in bench mode"
+        )]
+        iterations: usize,
+        #[clap(long, help = "The interpreter thread's stack size in bytes")]
+        stack_size: Option<usize>,
         #[cfg(feature = "audio")]
         #[clap(flatten)]
         audio_options: AudioOptions,
+        #[cfg(feature = "http")]
+        #[clap(long, hide = true, help = "Write shown images to this file instead")]
+        preview_image: Option<PathBuf>,
+        #[clap(long, help = "Read newline-separated program arguments from a file")]
+        args_file: Option<PathBuf>,
         #[clap(trailing_var_arg = true, help = "Arguments to pass to the program")]
         args: Vec<String>,
     },
-    #[clap(about = "Build an assembly (the .uasm format is currently unstable)")]
+    #[clap(
+        about = "Build an assembly (the .uasm format is unstable and only loads in the same Uiua version)"
+    )]
     Build {
         path: Option<PathBuf>,
         #[clap(short, long, help = "The path to the output file")]
@@ -686,12 +1316,27 @@ enum App {
     },
     #[clap(about = "Evaluate an expression and print its output")]
     Eval {
+        #[clap(help = "The code to evaluate, or - to read it from stdin")]
         code: String,
-        #[clap(long, help = "Don't colorize stack output")]
+        #[clap(long, help = "Don't colorize output")]
         no_color: bool,
+        #[clap(long, help = "Buffer stdout until it is flushed or the program ends")]
+        buffered_output: bool,
+        #[clap(
+            long,
+            value_name = "PATH",
+            help = "Run a file first so that its bindings are in scope. Can be repeated"
+        )]
+        preload: Vec<PathBuf>,
+        #[clap(long, help = "Seed the random number generator")]
+        seed: Option<u64>,
+        #[clap(long, help = "The interpreter thread's stack size in bytes")]
+        stack_size: Option<usize>,
         #[cfg(feature = "audio")]
         #[clap(flatten)]
         audio_options: AudioOptions,
+        #[clap(long, help = "Read newline-separated program arguments from a file")]
+        args_file: Option<PathBuf>,
         #[clap(trailing_var_arg = true, help = "Arguments to pass to the program")]
         args: Vec<String>,
     },
@@ -700,6 +1345,27 @@ enum App {
         path: Option<PathBuf>,
         #[clap(flatten)]
         formatter_options: FormatterOptions,
+        #[clap(flatten)]
+        diagnostic_options: DiagnosticOptions,
+        #[clap(long, help = "Seed the random number generator")]
+        seed: Option<u64>,
+        #[clap(
+            long,
+            help = "Only run test scopes whose comments or binding names contain this string"
+        )]
+        filter: Option<String>,
+        #[clap(long, help = "Write a JUnit XML test report to this path")]
+        junit: Option<PathBuf>,
+        #[clap(
+            long,
+            default_value = "human",
+            value_parser = ["human", "json"],
+            help = "How to print errors and diagnostics. \
+                    json prints one JSON object per message to stderr."
+        )]
+        error_format: String,
+        #[clap(long, help = "Read newline-separated program arguments from a file")]
+        args_file: Option<PathBuf>,
         #[clap(trailing_var_arg = true, help = "Arguments to pass to the program")]
         args: Vec<String>,
     },
@@ -707,7 +1373,13 @@ enum App {
     Watch {
         #[clap(long, help = "Don't format the file before running")]
         no_format: bool,
-        #[clap(long, help = "Don't colorize stack output")]
+        #[clap(
+            long,
+            conflicts_with = "no_format",
+            help = "Format the file before running, even if uiua.toml sets no-format"
+        )]
+        format: bool,
+        #[clap(long, help = "Don't colorize output")]
         no_color: bool,
         #[clap(flatten)]
         formatter_options: FormatterOptions,
@@ -715,16 +1387,105 @@ enum App {
         clear: bool,
         #[clap(long, help = "Read stdin from file")]
         stdin_file: Option<PathBuf>,
+        #[clap(long, help = "The interpreter thread's stack size in bytes")]
+        stack_size: Option<usize>,
+        #[clap(long, help = "The directory to watch for changes")]
+        watch_dir: Option<PathBuf>,
+        #[clap(long, help = "Don't watch subdirectories")]
+        no_recursive: bool,
+        #[clap(
+            long,
+            default_value_t = DEFAULT_DEBOUNCE_MS,
+            help = "How long to wait in milliseconds after a change before running"
+        )]
+        debounce: u64,
+        #[cfg(feature = "http")]
+        #[clap(
+            long,
+            value_name = "PORT",
+            num_args = 0..=1,
+            default_missing_value = "8080",
+            help = "Serve the last image the program showed at http://localhost:PORT \
+                    instead of printing it to the terminal"
+        )]
+        serve: Option<u16>,
+        #[clap(
+            long,
+            help = "Format and run the file once, then exit with its status instead of watching"
+        )]
+        #[cfg_attr(feature = "http", clap(conflicts_with = "serve"))]
+        once: bool,
+        #[clap(long, help = "Read newline-separated program arguments from a file")]
+        args_file: Option<PathBuf>,
         #[clap(trailing_var_arg = true, help = "Arguments to pass to the program")]
         args: Vec<String>,
     },
     #[clap(about = "Format a Uiua file or all files in the current directory")]
     Fmt {
-        path: Option<PathBuf>,
+        #[clap(help = "The files to format")]
+        paths: Vec<PathBuf>,
+        #[clap(
+            long,
+            help = "Format files matching a glob pattern, like \"src/**/*.ua\""
+        )]
+        glob: Option<String>,
         #[clap(flatten)]
         formatter_options: FormatterOptions,
         #[clap(long, help = "Format lines read from stdin")]
         io: bool,
+        #[clap(
+            long,
+            help = "Check that files are formatted without modifying them (ignores --io)"
+        )]
+        check: bool,
+        #[clap(
+            long,
+            help = "Print a diff of the changes that would be made without modifying any files"
+        )]
+        diff: bool,
+    },
+    #[clap(about = "List the files a Uiua file imports without running it")]
+    Deps {
+        path: Option<PathBuf>,
+        #[clap(long, help = "Print imports as a tree")]
+        tree: bool,
+    },
+    #[clap(about = "Print a Graphviz DOT graph of a Uiua file's imports")]
+    Graph {
+        path: Option<PathBuf>,
+        #[clap(short, long, help = "The path to write the graph to instead of stdout")]
+        output: Option<PathBuf>,
+    },
+    #[clap(about = "Bundle a Uiua file and the files it imports into a single file")]
+    Bundle {
+        path: Option<PathBuf>,
+        #[clap(
+            short,
+            long,
+            help = "The path to write the bundled file to instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[clap(about = "Print the syntax tree of a Uiua file without running it")]
+    Ast {
+        path: Option<PathBuf>,
+        #[clap(long, help = "Print the syntax tree as JSON")]
+        json: bool,
+    },
+    #[clap(about = "Print the documentation for a primitive")]
+    Doc {
+        #[clap(help = "The name, glyph, or name prefix of the primitive")]
+        name: String,
+    },
+    #[clap(about = "List all primitives with their glyphs, names, and signatures")]
+    Glyphs {
+        #[clap(
+            long,
+            default_value = "ascii",
+            value_parser = ["ascii", "markdown", "json"],
+            help = "The output format"
+        )]
+        format: String,
     },
     #[clap(about = "Find some Uiua code that matches the given unformatted text")]
     Find {
@@ -734,6 +1495,20 @@ enum App {
         #[clap(long, help = "Disable color and other formatting")]
         raw: bool,
     },
+    #[clap(about = "Print version information")]
+    Version {
+        #[clap(
+            long,
+            help = "Also print enabled features, the build target, and the Rust version"
+        )]
+        verbose: bool,
+    },
+    #[clap(
+        name = "selftest",
+        alias = "self-test",
+        about = "Run built-in example programs to verify that the interpreter works"
+    )]
+    SelfTest,
     #[clap(about = "Run the Uiua interpreter in a REPL")]
     Repl {
         #[clap(help = "A Uiua file to run before the REPL starts")]
@@ -743,8 +1518,27 @@ enum App {
         #[cfg(feature = "audio")]
         #[clap(flatten)]
         audio_options: AudioOptions,
-        #[clap(short = 's', long, help = "Don't clear the stack after each line")]
+        #[clap(long, help = "Clear the stack after each line")]
+        clear_stack: bool,
+        /// The stack is kept by default, so this is only accepted for compatibility
+        #[clap(short = 's', long, hide = true)]
         stack: bool,
+        #[clap(
+            long,
+            value_name = "PATH",
+            help = "Run a file first so that its bindings are in scope. Can be repeated"
+        )]
+        preload: Vec<PathBuf>,
+        #[clap(
+            long,
+            value_name = "PATH",
+            help = "Append each successful line to a session file, restoring it first if it exists"
+        )]
+        session: Option<PathBuf>,
+        #[clap(long, help = "Seed the random number generator")]
+        seed: Option<u64>,
+        #[clap(long, help = "Read newline-separated program arguments from a file")]
+        args_file: Option<PathBuf>,
         #[clap(trailing_var_arg = true)]
         args: Vec<String>,
     },
@@ -784,19 +1578,154 @@ enum ModuleCommand {
     },
 }
 
+/// Project-wide defaults for command line options, read from a `uiua.toml` file
+///
+/// Options passed on the command line take precedence.
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ProjectConfig {
+    mode: Option<String>,
+    no_format: bool,
+    seed: Option<u64>,
+    format_config: Option<PathBuf>,
+}
+
+impl ProjectConfig {
+    /// Search the current directory and its ancestors for a `uiua.toml` file
+    fn load() -> Self {
+        let Ok(mut dir) = env::current_dir() else {
+            return Self::default();
+        };
+        loop {
+            let path = dir.join("uiua.toml");
+            if path.exists() {
+                let res = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|s| toml::from_str::<Self>(&s).map_err(|e| e.to_string()))
+                    .and_then(|config| {
+                        if let Some(mode) = &config.mode {
+                            mode.parse::<RunMode>()?;
+                        }
+                        Ok(config)
+                    });
+                match res {
+                    Ok(mut config) => {
+                        // The formatter config path is relative to the project root
+                        if let Some(format_config) = &mut config.format_config {
+                            *format_config = dir.join(&*format_config);
+                        }
+                        return config;
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to load {}: {e}", path.display());
+                        exit(1);
+                    }
+                }
+            }
+            if !dir.pop() {
+                return Self::default();
+            }
+        }
+    }
+    /// Fill in options that were not passed on the command line
+    fn apply(self, mut app: App) -> App {
+        let mode_default = self.mode.as_deref().and_then(|mode| mode.parse().ok());
+        let apply_format_config = |options: &mut FormatterOptions| {
+            if options.format_config_source.is_none() {
+                options.format_config_source =
+                    (self.format_config.clone()).map(FormatConfigSource::Path);
+            }
+        };
+        match &mut app {
+            App::Run {
+                no_format,
+                format,
+                mode,
+                seed,
+                formatter_options,
+                ..
+            } => {
+                *no_format |= self.no_format && !*format;
+                *mode = mode.or(mode_default);
+                *seed = seed.or(self.seed);
+                apply_format_config(formatter_options);
+            }
+            App::Eval { seed, .. } => *seed = seed.or(self.seed),
+            App::Test {
+                seed,
+                formatter_options,
+                ..
+            }
+            | App::Repl {
+                seed,
+                formatter_options,
+                ..
+            } => {
+                *seed = seed.or(self.seed);
+                apply_format_config(formatter_options);
+            }
+            App::Watch {
+                no_format,
+                format,
+                formatter_options,
+                ..
+            } => {
+                *no_format |= self.no_format && !*format;
+                apply_format_config(formatter_options);
+            }
+            App::Fmt {
+                formatter_options, ..
+            } => apply_format_config(formatter_options),
+            _ => {}
+        }
+        app
+    }
+}
+
 #[derive(clap::Args)]
 struct FormatterOptions {
     #[clap(
         long = "format-config",
-        default_value_t = FormatConfigSource::SearchFile,
-        help = "Select the formatter configuration source (one of search-file, default, or a path to a fmt.ua file)"
+        help = "Select the formatter configuration source (one of search-file, default, or a path to a fmt.ua file) [default: search-file]"
     )]
-    format_config_source: FormatConfigSource,
+    format_config_source: Option<FormatConfigSource>,
 }
 
-#[cfg(feature = "audio")]
-#[derive(clap::Args)]
-struct AudioOptions {
+impl FormatterOptions {
+    /// The selected formatter configuration source, searching for a file if none was selected
+    fn format_config_source(&self) -> FormatConfigSource {
+        (self.format_config_source.clone()).unwrap_or(FormatConfigSource::SearchFile)
+    }
+}
+
+#[derive(clap::Args)]
+struct DiagnosticOptions {
+    #[clap(long, help = "Treat warnings as errors")]
+    strict: bool,
+    #[clap(
+        long,
+        value_name = "CATEGORY",
+        help = "Treat a category of diagnostic as an error \
+                (one of shadowing, unused-binding, or stack-residue). Can be repeated"
+    )]
+    deny: Vec<DiagnosticCategory>,
+}
+
+impl DiagnosticOptions {
+    fn apply<'a>(&self, comp: &'a mut Compiler) -> &'a mut Compiler {
+        if self.strict {
+            comp.deny_diagnostic(DiagnosticKind::Warning);
+        }
+        for &category in &self.deny {
+            comp.deny_category(category);
+        }
+        comp
+    }
+}
+
+#[cfg(feature = "audio")]
+#[derive(clap::Args)]
+struct AudioOptions {
     #[clap(long, help = "The start time of audio streaming")]
     audio_time: Option<f64>,
     #[clap(long, help = "The port to update audio time on")]
@@ -816,6 +1745,83 @@ fn setup_audio(options: AudioOptions) {
     }
 }
 
+/// Run an assembly repeatedly, each time in a new runtime, and print timing statistics
+///
+/// Afterwards, `rt` is the runtime of the last run, holding the stack from the first run.
+/// Returns the instruction timings summed over all runs.
+fn bench(
+    rt: &mut Uiua,
+    asm: &Assembly,
+    iterations: usize,
+    new_rt: impl Fn() -> Uiua,
+) -> UiuaResult<Vec<(Primitive, Duration)>> {
+    let iterations = iterations.max(1);
+    let mut times = Vec::with_capacity(iterations);
+    let mut instr_timings: HashMap<Primitive, Duration> = HashMap::new();
+    let mut first_stack: Option<Vec<Value>> = None;
+    let mut warned = false;
+    for i in 0..iterations {
+        *rt = new_rt();
+        let start = Instant::now();
+        rt.run_asm(asm)?;
+        times.push(start.elapsed());
+        for (prim, time) in rt.instr_timings() {
+            *instr_timings.entry(prim).or_default() += time;
+        }
+        let stack = rt.take_stack();
+        if let Some(first) = &first_stack {
+            if !warned && *first != stack {
+                eprintln!(
+                    "{} run {} produced a different result than the first run",
+                    "Warning:".yellow().bold(),
+                    i + 1
+                );
+                warned = true;
+            }
+        } else {
+            first_stack = Some(stack);
+        }
+    }
+    times.sort();
+    let min = times[0];
+    let max = times[times.len() - 1];
+    let median = if times.len() % 2 == 0 {
+        (times[times.len() / 2 - 1] + times[times.len() / 2]) / 2
+    } else {
+        times[times.len() / 2]
+    };
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+    println!("{iterations} runs");
+    println!("min:    {min:?}");
+    println!("median: {median:?}");
+    println!("mean:   {mean:?}");
+    println!("max:    {max:?}");
+    for val in first_stack.unwrap_or_default() {
+        rt.push(val);
+    }
+    let mut instr_timings: Vec<_> = instr_timings.into_iter().collect();
+    instr_timings.sort_by(|(_, a), (_, b)| b.cmp(a));
+    Ok(instr_timings)
+}
+
+/// The default stack size of the interpreter thread
+const DEFAULT_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Run a function on a thread with the given stack size
+fn with_stack_size<T: Send>(stack_size: Option<usize>, f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(stack_size.unwrap_or(DEFAULT_STACK_SIZE))
+            .spawn_scoped(scope, f)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to spawn interpreter thread: {e}");
+                exit(1)
+            })
+            .join()
+            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+    })
+}
+
 fn uiua_files(root: &Path) -> UiuaResult<Vec<PathBuf>> {
     fn rec(root: &Path, acc: &mut Vec<PathBuf>) -> UiuaResult<()> {
         for entry in fs::read_dir(root).map_err(|e| UiuaError::format(root.into(), e))? {
@@ -927,9 +1933,150 @@ fn update(main: bool, check: bool) {
     }
 }
 
-fn format_single_file(path: PathBuf, config: &FormatConfig) -> Result<(), UiuaError> {
-    format_file(path, config)?;
-    Ok(())
+/// Format each of the given files with its own configuration
+///
+/// Errors are reported, but do not stop the remaining files from being formatted.
+/// Returns whether all files were formatted successfully.
+fn format_paths(paths: &[PathBuf], source: &FormatConfigSource) -> bool {
+    let mut success = true;
+    for path in paths {
+        if let Err(e) = FormatConfig::from_source(source.clone(), Some(path))
+            .and_then(|config| format_file(path, &config))
+        {
+            println!("{}", e.report());
+            success = false;
+        }
+    }
+    success
+}
+
+/// Check that each of the given files is already formatted, without modifying any of them
+///
+/// The paths of unformatted files are printed, or a diff of the changes if `diff` is set,
+/// followed by a summary.
+/// Returns whether all files are formatted.
+fn check_paths(paths: &[PathBuf], source: &FormatConfigSource, diff: bool) -> bool {
+    let mut unformatted = 0;
+    let mut success = true;
+    for path in paths {
+        let res = FormatConfig::from_source(source.clone(), Some(path)).and_then(|config| {
+            let input = fs::read_to_string(path).map_err(|e| UiuaError::load(path.clone(), e))?;
+            let output = format(&input, path, &config)?.output;
+            Ok((input, output))
+        });
+        match res {
+            Ok((input, output)) if input == output => {}
+            Ok((input, output)) => {
+                if diff {
+                    print_diff(path, &input, &output);
+                } else {
+                    println!("{}", path.display());
+                }
+                unformatted += 1;
+            }
+            Err(e) => {
+                println!("{}", e.report());
+                success = false;
+            }
+        }
+    }
+    match unformatted {
+        0 => println!("All files are formatted"),
+        1 => println!("1 file would be reformatted"),
+        n => println!("{n} files would be reformatted"),
+    }
+    success && unformatted == 0
+}
+
+/// Print a unified diff between two versions of a file
+fn print_diff(path: &Path, old: &str, new: &str) {
+    const CONTEXT: usize = 3;
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // Only diff the lines between the common prefix and suffix
+    let prefix = (old.iter().zip(&new)).take_while(|(a, b)| a == b).count();
+    let suffix = (old[prefix..].iter().rev())
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    // Longest common subsequence lengths of every pair of suffixes
+    let (n, m) = (old_mid.len(), new_mid.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_mid[i] == new_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    // Build the edit script as (kind, old index, new index)
+    let mut edits: Vec<(char, usize, usize)> = (0..prefix).map(|i| (' ', i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_mid[i] == new_mid[j] {
+            edits.push((' ', prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(('-', prefix + i, prefix + j));
+            i += 1;
+        } else {
+            edits.push(('+', prefix + i, prefix + j));
+            j += 1;
+        }
+    }
+    edits.extend((0..suffix).map(|k| (' ', prefix + n + k, prefix + m + k)));
+    // Group changes into hunks with surrounding context
+    let changes: Vec<usize> = (edits.iter().enumerate())
+        .filter(|(_, (kind, ..))| *kind != ' ')
+        .map(|(k, _)| k)
+        .collect();
+    println!("--- {}", path.display());
+    println!("+++ {}", path.display());
+    let mut k = 0;
+    while k < changes.len() {
+        let start = changes[k].saturating_sub(CONTEXT);
+        let mut end = changes[k];
+        k += 1;
+        while k < changes.len() && changes[k] <= end + 2 * CONTEXT {
+            end = changes[k];
+            k += 1;
+        }
+        let hunk = &edits[start..(end + CONTEXT + 1).min(edits.len())];
+        let old_count = hunk.iter().filter(|(kind, ..)| *kind != '+').count();
+        let new_count = hunk.iter().filter(|(kind, ..)| *kind != '-').count();
+        let (_, old_start, new_start) = hunk[0];
+        println!(
+            "@@ -{},{old_count} +{},{new_count} @@",
+            old_start + (old_count > 0) as usize,
+            new_start + (new_count > 0) as usize
+        );
+        for &(kind, i, j) in hunk {
+            let line = if kind == '+' { new[j] } else { old[i] };
+            println!("{kind}{line}");
+        }
+    }
+}
+
+/// Find all .ua files whose paths match a glob pattern
+fn glob_uiua_files(pattern: &str) -> Vec<PathBuf> {
+    let paths = glob::glob(pattern).unwrap_or_else(|e| {
+        eprintln!("Invalid glob pattern {pattern:?}: {e}");
+        exit(1)
+    });
+    // Entries that cannot be read are skipped
+    let mut paths: Vec<PathBuf> = paths
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "ua"))
+        .collect();
+    paths.sort();
+    paths
 }
 
 fn format_multi_files(config: &FormatConfig) -> Result<(), UiuaError> {
@@ -939,10 +2086,239 @@ fn format_multi_files(config: &FormatConfig) -> Result<(), UiuaError> {
     Ok(())
 }
 
+/// Cargo features that may be enabled, for `uiua version --verbose`
+const FEATURES: &[(&str, bool)] = &[
+    ("audio", cfg!(feature = "audio")),
+    ("audio_encode", cfg!(feature = "audio_encode")),
+    ("clipboard", cfg!(feature = "clipboard")),
+    ("csv", cfg!(feature = "csv")),
+    ("ffi", cfg!(feature = "ffi")),
+    ("fft", cfg!(feature = "fft")),
+    ("font_shaping", cfg!(feature = "font_shaping")),
+    ("gif", cfg!(feature = "gif")),
+    ("glob", cfg!(feature = "glob")),
+    ("gzip", cfg!(feature = "gzip")),
+    ("hash", cfg!(feature = "hash")),
+    ("http", cfg!(feature = "http")),
+    ("image", cfg!(feature = "image")),
+    ("invoke", cfg!(feature = "invoke")),
+    ("json5", cfg!(feature = "json5")),
+    ("lsp", cfg!(feature = "lsp")),
+    ("native_sys", cfg!(feature = "native_sys")),
+    ("opt", cfg!(feature = "opt")),
+    ("profile", cfg!(feature = "profile")),
+    ("raw_mode", cfg!(feature = "raw_mode")),
+    ("stand", cfg!(feature = "stand")),
+    ("terminal_image", cfg!(feature = "terminal_image")),
+    ("tls", cfg!(feature = "tls")),
+    ("toml", cfg!(feature = "toml")),
+    ("trash", cfg!(feature = "trash")),
+    ("viewer", cfg!(feature = "viewer")),
+    ("webcam", cfg!(feature = "webcam")),
+    ("xlsx", cfg!(feature = "xlsx")),
+];
+
+fn print_version(verbose: bool) {
+    println!("uiua {}", env!("CARGO_PKG_VERSION"));
+    if !verbose {
+        return;
+    }
+    let enabled: Vec<&str> = (FEATURES.iter())
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect();
+    println!("features: {}", enabled.join(", "));
+    println!("target: {}", env!("UIUA_BUILD_TARGET"));
+    println!("rustc: {}", env!("UIUA_RUSTC_VERSION"));
+}
+
+/// Example programs run by `uiua selftest`, as `(name, source, expected output)`
+///
+/// The expected output is any printed text followed by the shown stack values, one per line.
+const SELF_TEST_EXAMPLES: &[(&str, &str, &str)] = &[
+    ("arithmetic", "+ 1 2", "3"),
+    ("range", "⇡5", "[0 1 2 3 4]"),
+    ("reduce", "/+ [1 2 3 4]", "10"),
+    ("shape", "△⍉ ↯2_3 0", "[3 2]"),
+    ("strings", "⇌ \"uiua\"", "\"auiu\""),
+    ("print", "&p \"Hello\" 5", "Hello\n5"),
+    ("bindings", "F ← ×2\nF 21", "42"),
+    #[cfg(feature = "image")]
+    ("image", "△◌°img img \"png\" ↯2_3_3 1", "[2 3 3]"),
+    #[cfg(feature = "audio_encode")]
+    ("audio", "⧻◌◌°audio audio \"wav\" 44100 ↯100 0", "100"),
+];
+
+fn self_test() -> bool {
+    let mut failures = 0;
+    for &(name, source, expected) in SELF_TEST_EXAMPLES {
+        let mut rt = Uiua::with_backend(SafeSys::new());
+        let output = rt.run_str(source).map(|_| {
+            let mut output = (rt.downcast_backend::<SafeSys>())
+                .map(SafeSys::take_stdout_string)
+                .unwrap_or_default();
+            for value in rt.take_stack() {
+                output.push_str(&value.show());
+                output.push('\n');
+            }
+            output.trim_end().to_string()
+        });
+        match output {
+            Ok(output) if output == expected => {
+                println!("{} {name}", "pass".bright_green().bold())
+            }
+            Ok(output) => {
+                failures += 1;
+                println!("{} {name}", "FAIL".bright_red().bold());
+                println!("  source:   {source}");
+                println!("  expected: {expected:?}");
+                println!("  got:      {output:?}");
+            }
+            Err(e) => {
+                failures += 1;
+                println!("{} {name}", "FAIL".bright_red().bold());
+                println!("  source: {source}");
+                println!("{}", e.report());
+            }
+        }
+    }
+    let total = SELF_TEST_EXAMPLES.len();
+    if failures == 0 {
+        println!("\nAll {total} examples passed");
+    } else {
+        println!("\n{failures} of {total} examples failed");
+    }
+    failures == 0
+}
+
+fn write_instr_timings(timings: Vec<(Primitive, Duration)>, path: &Path) {
+    let timings: serde_json::Map<String, serde_json::Value> = (timings.into_iter())
+        .map(|(prim, time)| (prim.name().into(), time.as_secs_f64().into()))
+        .collect();
+    let json = serde_json::to_string_pretty(&timings).unwrap();
+    if let Err(e) = fs::write(path, json) {
+        eprintln!("Failed to write instruction timings: {e}");
+    }
+}
+
+/// Prepend arguments read from a file to the command line arguments
+fn load_args_file(args_file: Option<PathBuf>, args: Vec<String>) -> Vec<String> {
+    let Some(path) = args_file else {
+        return args;
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => (contents.lines().map(Into::into)).chain(args).collect(),
+        Err(e) => {
+            eprintln!("Failed to read args file {}: {e}", path.display());
+            exit(1)
+        }
+    }
+}
+
+fn stack_json(stack: Vec<Value>, rt: &Uiua) -> String {
+    let list = Value::from(Array::from_iter(stack.into_iter().map(Boxed)));
+    list.to_json_string(rt).unwrap_or_else(fail)
+}
+
+fn print_stack_json(stack: Vec<Value>, rt: &Uiua) {
+    println!("{}", stack_json(stack, rt));
+}
+
+/// Render a stack as tab-separated values, one value after another
+///
+/// Values that cannot be shown as tables fall back to their normal representation.
+fn stack_tsv(stack: &[Value]) -> String {
+    let rendered: Vec<String> = (stack.iter())
+        .map(|value| {
+            value.to_tsv().unwrap_or_else(|| {
+                eprintln!(
+                    "{} a rank-{} array cannot be shown as TSV",
+                    "Note:".bright_blue().bold(),
+                    value.rank()
+                );
+                value.show()
+            })
+        })
+        .collect();
+    rendered.join("\n")
+}
+
+fn print_stack_tsv(stack: &[Value]) {
+    if !stack.is_empty() {
+        println!("{}", stack_tsv(stack));
+    }
+}
+
+fn write_stack(stack: Vec<Value>, rt: &Uiua, json: bool, tsv: bool, path: &Path) {
+    let mut rendered = if json {
+        stack_json(stack, rt)
+    } else if tsv {
+        stack_tsv(&stack)
+    } else {
+        stack.iter().map(Value::show).collect::<Vec<_>>().join("\n")
+    };
+    if !rendered.is_empty() {
+        rendered.push('\n');
+    }
+    if let Err(e) = fs::write(path, rendered) {
+        eprintln!("Failed to write {}: {e}", path.display());
+        exit(1);
+    }
+}
+
+/// Print an error as a report, or as JSON lines on stderr
+fn print_error(e: &UiuaError, json: bool) {
+    if json {
+        for message in e.json_messages() {
+            eprintln!("{}", serde_json::to_string(&message).unwrap());
+        }
+    } else {
+        println!("{}", e.report());
+    }
+}
+
+/// Print a compiler's diagnostics as JSON lines on stderr
+fn print_json_diagnostics(comp: &mut Compiler) {
+    for diagnostic in comp.take_diagnostics() {
+        eprintln!(
+            "{}",
+            serde_json::to_string(&diagnostic.json_message()).unwrap()
+        );
+    }
+}
+
+/// Print the stack as it was when the program failed
+fn print_error_stack(rt: &mut Uiua, color: bool) {
+    let stack = rt.take_error_stack();
+    println!("{}", "Stack at error:".bold());
+    if stack.is_empty() {
+        println!("(empty)");
+    } else {
+        print_stack(&stack, color);
+    }
+    println!();
+}
+
 fn print_stack(stack: &[Value], color: bool) {
+    print_stack_impl(stack, color, false)
+}
+
+/// Print the stack in the REPL, marking the top value when there are several
+fn print_repl_stack(stack: &[Value], color: bool) {
+    print_stack_impl(stack, color, stack.len() > 1)
+}
+
+fn print_stack_impl(stack: &[Value], color: bool, mark_top: bool) {
+    let marker = |i: usize| {
+        if mark_top && i == stack.len() - 1 {
+            format!(" {}", "← top".dimmed())
+        } else {
+            String::new()
+        }
+    };
     if stack.len() == 1 || !color {
-        for value in stack {
-            println!("{}", value.show());
+        for (i, value) in stack.iter().enumerate() {
+            println!("{}{}", value.show(), marker(i));
         }
         return;
     }
@@ -961,45 +2337,137 @@ fn print_stack(stack: &[Value], color: bool) {
             5 => (w, b, w),
             _ => unreachable!(),
         };
-        println!("{}", value.show().truecolor(r, g, b));
+        println!("{}{}", value.show().truecolor(r, g, b), marker(i));
     }
 }
 
-fn repl(mut env: Uiua, mut compiler: Compiler, color: bool, stack: bool, config: FormatConfig) {
+fn repl(
+    mut env: Uiua,
+    mut compiler: Compiler,
+    color: bool,
+    clear_stack: bool,
+    config: FormatConfig,
+    session: Option<PathBuf>,
+) {
     env = env.with_interrupt_hook(|| PRESSED_CTRL_C.swap(false, Ordering::Relaxed));
     compiler.pre_eval_mode(PreEvalMode::Line);
+    // Restore the session
+    let session_base = (session.as_ref())
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    if let Some(path) = session.as_ref().filter(|_| !session_base.is_empty()) {
+        compiler.load_file(path).unwrap_or_else(fail);
+        env.run_compiler(&mut compiler).unwrap_or_else(fail);
+        if clear_stack {
+            env.take_stack();
+        }
+    }
+    let base_compiler = compiler.clone();
+    let base_stack = env.stack().to_vec();
+    let mut lines: Vec<String> = Vec::new();
     println!(
         "Uiua {} (end with ctrl+C, type `help` for a list of commands)\n",
         env!("CARGO_PKG_VERSION")
     );
     let mut line_reader = DefaultEditor::new().expect("Failed to read from Stdin");
-    loop {
+    let history_path = repl_history_path();
+    if let Some(path) = &history_path {
+        _ = line_reader.load_history(path);
+    }
+    'repl: loop {
         let mut code = match line_reader.readline("    ") {
             Ok(code) => {
                 match code.trim() {
                     "help" => {
                         println!(
                             "\n\
-                            clear - Clear the stack \n\
-                            exit  - Exit the repl \n\
-                            help  - Show this message \n\
+                            stack    - Show the stack \n\
+                            clear    - Clear the stack \n\
+                            bindings - List bound names \n\
+                            type <x> - Show the signature of some code without running it \n\
+                            undo     - Undo the last successful line \n\
+                            exit     - Exit the repl \n\
+                            help     - Show this message \n\
                             "
                         );
                         continue;
                     }
+                    "stack" => {
+                        print_repl_stack(env.stack(), color);
+                        continue;
+                    }
+                    "bindings" => {
+                        print_bindings(compiler.assembly());
+                        continue;
+                    }
                     "clear" | "cls" => {
                         env.take_stack();
                         println!();
                         continue;
                     }
+                    "undo" => {
+                        if lines.pop().is_none() {
+                            println!("Nothing to undo\n");
+                            continue;
+                        }
+                        // Rebuild the runtime from the remaining lines
+                        compiler = base_compiler.clone();
+                        env.take_stack();
+                        for val in base_stack.clone() {
+                            env.push(val);
+                        }
+                        for line in &lines {
+                            let res = (compiler.load_str(line).map(drop))
+                                .and_then(|()| env.run_compiler(&mut compiler));
+                            if let Err(e) = res {
+                                eprintln!("{}", e.report());
+                                break;
+                            }
+                            if clear_stack {
+                                env.take_stack();
+                            }
+                        }
+                        if let Some(path) = &session {
+                            let mut contents = session_base.clone();
+                            for line in &lines {
+                                contents.push_str(line);
+                                contents.push('\n');
+                            }
+                            if let Err(e) = fs::write(path, contents) {
+                                eprintln!("Failed to write session file: {e}");
+                            }
+                        }
+                        print_repl_stack(env.stack(), color);
+                        continue;
+                    }
                     "exit" => break,
-                    _ => {}
+                    trimmed => {
+                        if let Some(expr) = trimmed.strip_prefix("type ") {
+                            match compiler.signature_of(expr) {
+                                Ok(sig) => println!("{sig}\n"),
+                                Err(e) => eprintln!("{}", e.report()),
+                            }
+                            continue;
+                        }
+                    }
                 }
                 code
             }
             Err(ReadlineError::Eof | ReadlineError::Interrupted) => break,
             Err(_) => panic!("Failed to read from Stdin"),
         };
+        // Keep reading lines until all brackets are closed
+        while unclosed_brackets(&code) > 0 {
+            match line_reader.readline("... ") {
+                Ok(line) => {
+                    code.push('\n');
+                    code.push_str(&line);
+                }
+                Err(ReadlineError::Interrupted) => continue 'repl,
+                Err(ReadlineError::Eof) => break 'repl,
+                Err(_) => panic!("Failed to read from Stdin"),
+            }
+        }
         if code.is_empty() {
             continue;
         }
@@ -1024,10 +2492,18 @@ fn repl(mut env: Uiua, mut compiler: Compiler, color: bool, stack: bool, config:
 
         match res {
             Ok(()) => {
-                print_stack(env.stack(), color);
-                if !stack {
+                print_repl_stack(env.stack(), color);
+                if clear_stack {
                     env.take_stack();
                 }
+                if let Some(path) = &session {
+                    let res = (fs::OpenOptions::new().create(true).append(true).open(path))
+                        .and_then(|mut file| writeln!(file, "{code}"));
+                    if let Err(e) = res {
+                        eprintln!("Failed to write session file: {e}");
+                    }
+                }
+                lines.push(code);
             }
             Err(e) => {
                 compiler = backup_comp;
@@ -1036,10 +2512,86 @@ fn repl(mut env: Uiua, mut compiler: Compiler, color: bool, stack: bool, config:
                     env.push(val);
                 }
                 eprintln!("{}", e.report());
-                print_stack(env.stack(), color);
+                print_repl_stack(env.stack(), color);
+            }
+        }
+    }
+    if let Some(path) = &history_path {
+        _ = line_reader.save_history(path);
+    }
+}
+
+fn print_bindings(asm: &Assembly) {
+    // Later bindings shadow earlier ones with the same name
+    let mut bindings = BTreeMap::new();
+    for binding in &asm.bindings {
+        let desc = match &binding.kind {
+            BindingKind::Const(Some(val)) => format!("{} {}", val.shape(), val.type_name()),
+            BindingKind::Func(f) => f.signature().to_string(),
+            _ => continue,
+        };
+        let name = binding.span.as_str(asm.inputs(), |s| s.to_string());
+        bindings.insert(name, desc);
+    }
+    let width = (bindings.keys())
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (name, desc) in bindings {
+        println!("{name:width$}  {desc}");
+    }
+}
+
+/// Run files so that their bindings are in scope for later code
+///
+/// Values left on the stack by the files are discarded
+fn preload_files(rt: &mut Uiua, comp: &mut Compiler, paths: &[PathBuf]) -> UiuaResult {
+    for path in paths {
+        comp.load_file(path)?;
+        rt.run_compiler(comp)?;
+    }
+    rt.take_stack();
+    Ok(())
+}
+
+fn repl_history_path() -> Option<PathBuf> {
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".uiua_history"))
+}
+
+/// Count the brackets in some code that have not been closed
+fn unclosed_brackets(code: &str) -> usize {
+    let mut depth = 0usize;
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '@' => {
+                chars.next();
             }
+            '$' if chars.peek() != Some(&'"') => {
+                // Raw strings go to the end of the line
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '#' => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+            }
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
         }
     }
+    depth
 }
 
 fn color_code(code: &str, compiler: &Compiler) -> String {
@@ -1156,6 +2708,414 @@ fn update_modules(modules: &[PathBuf]) -> io::Result<()> {
     Ok(())
 }
 
+/// The import graph of a Uiua file, found by parsing rather than running
+struct ImportGraph {
+    root: PathBuf,
+    /// Each file's direct imports, in order of appearance
+    edges: BTreeMap<PathBuf, Vec<PathBuf>>,
+    /// Import chains that lead back to a file already being loaded
+    cycles: Vec<Vec<PathBuf>>,
+}
+
+impl ImportGraph {
+    fn load(root: &Path) -> UiuaResult<Self> {
+        let mut graph = ImportGraph {
+            root: normalize_import_path(root),
+            edges: BTreeMap::new(),
+            cycles: Vec::new(),
+        };
+        let mut stack = Vec::new();
+        graph.visit(graph.root.clone(), &mut stack)?;
+        Ok(graph)
+    }
+    fn visit(&mut self, path: PathBuf, stack: &mut Vec<PathBuf>) -> UiuaResult {
+        if let Some(i) = stack.iter().position(|p| p == &path) {
+            let mut cycle = stack[i..].to_vec();
+            cycle.push(path);
+            self.cycles.push(cycle);
+            return Ok(());
+        }
+        if self.edges.contains_key(&path) {
+            return Ok(());
+        }
+        let imports = file_imports(&path)?;
+        self.edges.insert(path.clone(), imports.clone());
+        stack.push(path);
+        for import in imports {
+            self.visit(import, stack)?;
+        }
+        stack.pop();
+        Ok(())
+    }
+    /// All files in the graph, with each file after the files it imports
+    fn load_order(&self) -> Vec<&PathBuf> {
+        fn visit<'a>(graph: &'a ImportGraph, path: &'a PathBuf, order: &mut Vec<&'a PathBuf>) {
+            if order.contains(&path) {
+                return;
+            }
+            for import in graph.edges.get(path).into_iter().flatten() {
+                visit(graph, import, order);
+            }
+            order.push(path);
+        }
+        let mut order = Vec::new();
+        visit(self, &self.root, &mut order);
+        order
+    }
+    /// All files transitively imported by the root, sorted
+    fn dependencies(&self) -> impl Iterator<Item = &PathBuf> {
+        self.edges.keys().filter(|path| **path != self.root)
+    }
+    fn print_tree(&self) {
+        fn print(graph: &ImportGraph, path: &Path, depth: usize, stack: &mut Vec<PathBuf>) {
+            let cyclic = stack.iter().any(|p| p == path);
+            let suffix = if cyclic { " (cycle)" } else { "" };
+            println!("{}{}{suffix}", "  ".repeat(depth), path.display());
+            if cyclic {
+                return;
+            }
+            stack.push(path.to_path_buf());
+            for import in graph.edges.get(path).into_iter().flatten() {
+                print(graph, import, depth + 1, stack);
+            }
+            stack.pop();
+        }
+        print(self, &self.root, 0, &mut Vec::new());
+    }
+    /// Render the graph in Graphviz DOT format
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph imports {\n");
+        for path in self.edges.keys() {
+            dot.push_str(&format!("    {:?};\n", path.display().to_string()));
+        }
+        for (from, imports) in &self.edges {
+            for to in imports {
+                dot.push_str(&format!(
+                    "    {:?} -> {:?};\n",
+                    from.display().to_string(),
+                    to.display().to_string()
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Parse a file and resolve the paths of the local files it imports
+///
+/// Git imports are skipped, as resolving them requires fetching the repository.
+fn file_imports(path: &Path) -> UiuaResult<Vec<PathBuf>> {
+    fn collect(items: &[Item], found: &mut Vec<String>) {
+        for item in items {
+            match item {
+                Item::Import(import) => found.push(import.path.value.clone()),
+                Item::Module(module) => collect(&module.value.items, found),
+                _ => {}
+            }
+        }
+    }
+    let input = fs::read_to_string(path).map_err(|e| UiuaError::load(path.into(), e))?;
+    let mut inputs = Inputs::default();
+    let (items, errors, _) = parse(&input, path, &mut inputs);
+    if !errors.is_empty() {
+        return Err(UiuaErrorKind::Parse(errors, inputs.into()).into());
+    }
+    let mut found = Vec::new();
+    collect(&items, &mut found);
+    let mut imports = Vec::new();
+    for import in found {
+        if import.trim().starts_with("git:") {
+            continue;
+        }
+        let target = resolve_local_import(path, &import);
+        if !imports.contains(&target) {
+            imports.push(target);
+        }
+    }
+    Ok(imports)
+}
+
+/// Check whether a file is a compiled assembly by looking for the `.uasm` header
+///
+/// This lets assemblies be run regardless of their extension.
+fn is_assembly(path: &Path) -> bool {
+    let mut header = [0; UASM_HEADER.len()];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|()| header == UASM_HEADER.as_bytes())
+}
+
+/// Resolve the path of a local import relative to the file that imports it
+fn resolve_local_import(from: &Path, import: &str) -> PathBuf {
+    let parent = from.parent().unwrap_or(Path::new("."));
+    let mut target = parent.join(import);
+    if !target.exists() && target.extension().is_none() {
+        target = target.with_extension("ua");
+    }
+    normalize_import_path(&target)
+}
+
+/// Inline all of a file's local imports into a single source file
+///
+/// Each imported file becomes a scoped module, placed before any file that imports it.
+/// Imports are replaced with bindings that alias the module and the items imported from it.
+/// Because all modules come first, an imported file's top-level code runs before
+/// any of the entry file's code rather than at the point of import.
+fn bundle(entry: &Path) -> UiuaResult<String> {
+    let graph = ImportGraph::load(entry)?;
+    if let Some(cycle) = graph.cycles.first() {
+        let chain: Vec<_> = cycle.iter().map(|p| p.display().to_string()).collect();
+        let message = format!("Import cycle detected: {}", chain.join(" -> "));
+        return Err(UiuaErrorKind::Run(Span::Builtin.sp(message), Default::default()).into());
+    }
+    let order = graph.load_order();
+    let mut sources = BTreeMap::new();
+    for path in &order {
+        let input = fs::read_to_string(path).map_err(|e| UiuaError::load(path.to_path_buf(), e))?;
+        sources.insert(*path, input);
+    }
+
+    // Name modules so that they do not collide with any identifier in any file
+    let taken: BTreeSet<&str> = (sources.values())
+        .flat_map(|input| input.split(|c: char| !is_ident_char(c)))
+        .filter(|ident| !ident.is_empty())
+        .collect();
+    let mut names = BTreeMap::new();
+    for path in graph.dependencies() {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut base: String = (stem.split(|c: char| !is_ident_char(c)))
+            .flat_map(|word| {
+                let mut chars = word.chars();
+                (chars.next().into_iter())
+                    .flat_map(char::to_uppercase)
+                    .chain(chars)
+            })
+            .collect();
+        if !base.starts_with(char::is_uppercase) {
+            base = format!("Module{base}");
+        }
+        let mut name = base.clone();
+        let mut n = 2;
+        while taken.contains(name.as_str()) || names.values().any(|other| *other == name) {
+            let sub: String = (n.to_string().chars())
+                .map(|d| SUBSCRIPT_NUMS[d as usize - '0' as usize])
+                .collect();
+            name = format!("{base}{sub}");
+            n += 1;
+        }
+        names.insert(path.clone(), name);
+    }
+
+    let mut bundled = String::new();
+    if (sources[&graph.root].lines()).any(|line| line.trim() == "# Experimental!") {
+        bundled.push_str("# Experimental!\n\n");
+    }
+    for path in order {
+        let source = bundle_rewrite_imports(path, &sources[path], &names)?;
+        let Some(name) = names.get(path) else {
+            bundled.push_str(&source);
+            continue;
+        };
+        bundled.push_str(&format!("┌─╴{name}\n"));
+        for line in source.lines() {
+            if !line.trim().is_empty() {
+                bundled.push_str("  ");
+                bundled.push_str(line);
+            }
+            bundled.push('\n');
+        }
+        bundled.push_str("└─╴\n\n");
+    }
+    Ok(bundled)
+}
+
+/// Replace a file's imports with bindings to the bundled modules
+fn bundle_rewrite_imports(
+    path: &Path,
+    input: &str,
+    names: &BTreeMap<PathBuf, String>,
+) -> UiuaResult<String> {
+    fn collect(items: Vec<Item>, found: &mut Vec<Import>) {
+        for item in items {
+            match item {
+                Item::Import(import) => found.push(import),
+                Item::Module(module) => collect(module.value.items, found),
+                _ => {}
+            }
+        }
+    }
+    let mut inputs = Inputs::default();
+    let (items, errors, _) = parse(input, path, &mut inputs);
+    if !errors.is_empty() {
+        return Err(UiuaErrorKind::Parse(errors, inputs.into()).into());
+    }
+    let mut imports = Vec::new();
+    collect(items, &mut imports);
+    let error = |span: &CodeSpan, message: String| -> UiuaError {
+        let inputs = inputs.clone().into();
+        UiuaErrorKind::Run(Span::Code(span.clone()).sp(message), inputs).into()
+    };
+    let mut output = input.to_string();
+    for import in imports.iter().rev() {
+        if import.path.value.trim().starts_with("git:") {
+            return Err(error(
+                &import.path.span,
+                "Git imports cannot be bundled".into(),
+            ));
+        }
+        let module = &names[&resolve_local_import(path, &import.path.value)];
+        let first = (import.name.as_ref()).map_or(&import.tilde_span, |name| &name.span);
+        let start = first.byte_range().start;
+        let end = import.span().byte_range().end;
+        let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+        let indent: String = input[line_start..start]
+            .chars()
+            .map(|c| if c.is_whitespace() { c } else { ' ' })
+            .collect();
+        let mut bindings = Vec::new();
+        if let Some(name) = &import.name {
+            bindings.push(format!("{} ← {module}", name.value));
+        }
+        for item in import.items() {
+            if ident_modifier_args(&item.value) > 0 {
+                return Err(error(
+                    &item.span,
+                    format!(
+                        "Imported modifier {} cannot be bundled. \
+                        Refer to it through its module instead.",
+                        item.value
+                    ),
+                ));
+            }
+            bindings.push(format!("{} ← {module}~{}", item.value, item.value));
+        }
+        output.replace_range(start..end, &bindings.join(&format!("\n{indent}")));
+    }
+    Ok(output)
+}
+
+/// Make a path relative to the current directory, the same way the compiler keys imports
+fn normalize_import_path(path: &Path) -> PathBuf {
+    let base = Path::new(".");
+    if let (Ok(canon_path), Ok(canon_base)) = (path.canonicalize(), base.canonicalize()) {
+        pathdiff::diff_paths(canon_path, canon_base).unwrap_or_else(|| path.to_path_buf())
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn print_prim_doc(name: &str) {
+    let mut chars = name.chars();
+    let glyph = chars.next().filter(|_| chars.next().is_none());
+    let prim = glyph
+        .and_then(Primitive::from_glyph)
+        .or_else(|| Primitive::from_format_name(name));
+    let prim = match prim {
+        Some(prim) => prim,
+        None => {
+            let candidates: Vec<_> = Primitive::non_deprecated()
+                .filter(|prim| prim.name().starts_with(name))
+                .collect();
+            match candidates.as_slice() {
+                [prim] => *prim,
+                [] => {
+                    eprintln!("No primitive matches {name:?}");
+                    exit(1);
+                }
+                _ => {
+                    eprintln!("{name:?} could be any of:");
+                    for prim in candidates {
+                        eprintln!("  {}", prim.format());
+                    }
+                    exit(1);
+                }
+            }
+        }
+    };
+    let sig = (prim.signature())
+        .map(|sig| format!(" {sig}"))
+        .unwrap_or_default();
+    println!("{}{sig}", prim.format().to_string().bold());
+    let doc = prim.doc();
+    println!("{}", doc.short_text());
+    for line in &doc.lines {
+        match line {
+            PrimDocLine::Text(frags) => println!("{}", PrimDoc::fragments_text(frags)),
+            PrimDocLine::Example(ex) => {
+                for line in ex.input().lines() {
+                    println!("  {}", line.bright_cyan());
+                }
+                match ex.output() {
+                    Ok(outputs) => {
+                        for line in outputs.iter().flat_map(|output| output.lines()) {
+                            println!("  {}", line.dimmed());
+                        }
+                    }
+                    Err(e) => println!("  {}", format!("Error: {e}").dimmed()),
+                }
+            }
+        }
+    }
+}
+
+fn print_glyphs(format: &str) {
+    let prims: Vec<(PrimClass, Primitive)> = PrimClass::all()
+        .flat_map(|class| class.primitives().map(move |prim| (class, prim)))
+        .filter(|(_, prim)| !prim.is_deprecated())
+        .collect();
+    let count = |n: Option<usize>| n.map_or_else(|| "?".into(), |n| n.to_string());
+    match format {
+        "json" => {
+            let list: Vec<_> = (prims.iter())
+                .map(|(class, prim)| {
+                    serde_json::json!({
+                        "glyph": prim.glyph(),
+                        "name": prim.name(),
+                        "class": format!("{class:?}"),
+                        "args": prim.args(),
+                        "outputs": prim.outputs(),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&list).unwrap());
+        }
+        "markdown" => {
+            println!("| Glyph | Name | Class | Args | Outputs |");
+            println!("| - | - | - | - | - |");
+            for (class, prim) in &prims {
+                let glyph = prim.glyph().map(|c| format!("`{c}`")).unwrap_or_default();
+                println!(
+                    "| {glyph} | {} | {class:?} | {} | {} |",
+                    prim.name(),
+                    count(prim.args()),
+                    count(prim.outputs())
+                );
+            }
+        }
+        _ => {
+            let name_width = prims.iter().map(|(_, p)| p.name().len()).max().unwrap_or(0);
+            let mut prev_class = None;
+            for (class, prim) in &prims {
+                if prev_class != Some(*class) {
+                    if prev_class.is_some() {
+                        println!();
+                    }
+                    println!("{class:?}");
+                    prev_class = Some(*class);
+                }
+                println!(
+                    "  {} {:name_width$} {} {}",
+                    prim.glyph().unwrap_or(' '),
+                    prim.name(),
+                    count(prim.args()),
+                    count(prim.outputs())
+                );
+            }
+        }
+    }
+}
+
 fn find(path: Option<PathBuf>, mut text: String, raw: bool) -> UiuaResult {
     if raw {
         colored::control::set_override(false);
@@ -1222,3 +3182,44 @@ fn find(path: Option<PathBuf>, mut text: String, raw: bool) -> UiuaResult {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_config_precedence() {
+        let config = || ProjectConfig {
+            no_format: true,
+            seed: Some(1),
+            format_config: Some(PathBuf::from("project.fmt.ua")),
+            ..Default::default()
+        };
+        let run = |args: &[&str]| {
+            let app = App::try_parse_from(["uiua", "run"].iter().chain(args)).unwrap();
+            let App::Run {
+                no_format,
+                seed,
+                formatter_options,
+                ..
+            } = config().apply(app)
+            else {
+                unreachable!()
+            };
+            (no_format, seed, formatter_options.format_config_source())
+        };
+        // The project config fills in options that were not passed
+        let project_source = FormatConfigSource::Path("project.fmt.ua".into());
+        assert_eq!(run(&[]), (true, Some(1), project_source));
+        // Options passed on the command line take precedence
+        assert_eq!(
+            run(&["--format", "--seed", "2", "--format-config", "search-file"]),
+            (false, Some(2), FormatConfigSource::SearchFile)
+        );
+        assert_eq!(
+            run(&["--format-config", "default"]).2,
+            FormatConfigSource::Default
+        );
+        assert!(App::try_parse_from(["uiua", "run", "--format", "--no-format"]).is_err());
+    }
+}
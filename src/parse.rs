@@ -292,13 +292,10 @@ impl<'i> Parser<'i> {
                         break;
                     }
                     self.try_spaces();
-                    let mut extra_newlines = false;
+                    // Each blank line gets its own empty item so the formatter can limit them
                     while self.try_exact(Newline).is_some() {
-                        extra_newlines = true;
-                        self.try_spaces();
-                    }
-                    if extra_newlines {
                         items.push(Item::Words(vec![Vec::new()]));
+                        self.try_spaces();
                     }
                 }
             }
@@ -812,7 +809,7 @@ impl<'i> Parser<'i> {
                     newlines += 1;
                     self.try_spaces();
                 }
-                if newlines > 1 {
+                for _ in 1..newlines {
                     lines.push(Vec::new());
                 }
             } else {
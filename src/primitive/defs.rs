@@ -3326,6 +3326,19 @@ primitive!(
     /// You can easily create a [map] with the headers as keys.
     /// ex: map⊙(⍉⋕)°⊂ °csv "#,Count\n1,5\n2,21\n3,8\n"
     (1, Csv, Encoding, "csv"),
+    /// Hash a string or list of bytes
+    ///
+    /// Expects the name of a hash algorithm and the data to hash.
+    /// The supported algorithms are `"md5"`, `"sha1"`, `"sha224"`, `"sha256"`, `"sha384"`, and `"sha512"`.
+    /// `"md5"` and `"sha1"` are not secure, so only use them for compatibility with existing checksums.
+    /// Strings are hashed as UTF-8. The result is the digest as a list of bytes.
+    /// ex: # Experimental!
+    ///   : hash "sha256" "Uiua"
+    /// ex: # Experimental!
+    ///   : hash "sha224" [1 2 3]
+    /// ex: # Experimental!
+    ///   : hash "md5" "Uiua"
+    (2, Hash, Encoding, "hash"),
     /// Encode an array into XLSX bytes
     ///
     /// XLSX is a spreadsheet format that can be edited in programs like Microsoft Excel, Google Sheets, and LibreOffice Calc.
@@ -3341,6 +3354,20 @@ primitive!(
     /// While it is not useful to display the output bytes here, we can see how the result of decoding works:
     /// ex: °xlsx xlsx . ↯3_6⇡18
     (1, Xlsx, Encoding, "xlsx"),
+    /// Compress a string or list of bytes with gzip
+    ///
+    /// Strings are compressed as UTF-8. The result is a list of bytes.
+    /// ex: # Experimental!
+    ///   : gzip "Uiua Uiua Uiua Uiua"
+    /// You can use [un][gzip] to decompress gzip bytes.
+    /// ex: # Experimental!
+    ///   : °utf°gzip gzip "Uiua Uiua Uiua Uiua"
+    /// Decompressing data that is not valid gzip is an error.
+    /// ex! # Experimental!
+    ///   : °gzip [1 2 3]
+    ///
+    /// [gzip] pairs well with [&frab] and [&fwa] for working with `.gz` files.
+    (1, Gzip, Encoding, "gzip"),
     /// Convert a value to its code representation
     ///
     /// ex: repr π
@@ -3378,9 +3405,16 @@ primitive!(
     /// A length 4 last axis is an RGB image with an alpha channel.
     ///
     /// You can decode a byte array into an image with [un][img].
+    /// The decoded image is always a rank 3 array whose last axis matches the image's color type: length 1 for grayscale, 2 for grayscale with alpha, 3 for RGB, and 4 for RGB with alpha.
+    /// If the bytes do not have a recognizable header, you can [un][img] a list of a format and the bytes to decode them as that format.
+    /// To always get the same number of channels, put a channel count from `1` to `4` before the bytes. For example, `°img {4 bytes}` always decodes to RGBA. A format and a channel count may both be given.
+    /// ex: △◌°img {4 img "png" [0_1 1_0]}
     ///
     /// Supported formats are `jpg`, `png`, `bmp`, `gif`, `ico`, and `qoi`.
     ///
+    /// The format may also be a list of the format and a quality from `1` to `100`. The quality only affects `jpg` images. It is ignored with a warning for other formats.
+    /// ex: ⧻img {"jpg" 50} ÷198⊞+.⇡100
+    ///
     /// See also: [&ims]
     (2, ImageEncode, Encoding, "img"),
     /// Encode a gif into a byte array
@@ -3412,6 +3446,8 @@ primitive!(
     ///
     /// Currently, only the `wav` format is supported.
     ///
+    /// To write audio to a file, pass the encoded bytes to [&fwa].
+    ///
     /// This simple example will load an audio file, halve its sample rate, and re-encode it.
     /// ex: ⍜(°audio &frab "test.wav")⊙⊓(⌊÷2|▽0.5)
     ///
@@ -3454,6 +3490,18 @@ primitive!(
     /// ex: # Experimental!
     ///   : ⬚[1 0 0] layout {100 0_1_0} "Green on Red!"
     (2, Layout, Encoding, "layout", Impure),
+    /// Render a list of numbers as a line chart image
+    ///
+    /// The result is a rank-3 RGBA image array which can be shown with [&ims] or encoded with [img].
+    /// ex: # Experimental!
+    ///   : plot ∿÷10⇡100
+    /// Non-finite values leave gaps in the line.
+    /// ex: # Experimental!
+    ///   : plot [1 2 NaN 4 3 ∞ 5 6]
+    /// The default size is 300 by 400 pixels. To choose a different size, pass a box list of the `[height width]` and the data.
+    /// ex: # Experimental!
+    ///   : △ plot {200_600 ⇡10}
+    (1, Plot, Encoding, "plot"),
 );
 
 macro_rules! impl_primitive {
@@ -3552,6 +3600,7 @@ impl_primitive!(
     (1, UnJson),
     (1, UnCsv),
     (1, UnXlsx),
+    (1, UnGzip),
     (1, UnFft),
     (1, UnDatetime),
     (2, ProgressiveIndexOf),
@@ -187,6 +187,7 @@ impl fmt::Display for ImplPrimitive {
             UnJson => write!(f, "{Un}{Json}"),
             UnCsv => write!(f, "{Un}{Csv}"),
             UnXlsx => write!(f, "{Un}{Xlsx}"),
+            UnGzip => write!(f, "{Un}{Gzip}"),
             UnFft => write!(f, "{Un}{Fft}"),
             UnDatetime => write!(f, "{Un}{DateTime}"),
             ImageDecode => write!(f, "{Un}{ImageEncode}"),
@@ -515,6 +516,7 @@ impl Primitive {
                 | (Astar | Triangle)
                 | Sys(Ffi | MemCopy | MemFree | TlsListen)
                 | (Stringify | Quote | Sig)
+                | (Hash | Gzip | Plot)
         )
     }
     /// Check if this primitive is deprecated
@@ -994,13 +996,16 @@ impl Primitive {
             Primitive::Regex => regex(env)?,
             Primitive::Json => env.monadic_ref_env(Value::to_json_string)?,
             Primitive::Csv => env.monadic_ref_env(Value::to_csv)?,
+            Primitive::Hash => encode::hash(env)?,
             Primitive::Xlsx => {
                 env.monadic_ref_env(|value, env| value.to_xlsx(env).map(EcoVec::from))?
             }
+            Primitive::Gzip => compress::gzip(env)?,
             Primitive::ImageEncode => encode::image_encode(env)?,
             Primitive::GifEncode => encode::gif_encode(env)?,
             Primitive::AudioEncode => encode::audio_encode(env)?,
             Primitive::Layout => env.dyadic_oo_env(encode::layout_text)?,
+            Primitive::Plot => plot::plot(env)?,
             Primitive::Astar => algorithm::astar(env)?,
             Primitive::Fft => algorithm::fft(env)?,
             Primitive::Stringify
@@ -1142,6 +1147,7 @@ impl ImplPrimitive {
                 let val = Value::from_xlsx(&xlsx, env)?;
                 env.push(val);
             }
+            ImplPrimitive::UnGzip => compress::ungzip(env)?,
             ImplPrimitive::UnFft => algorithm::unfft(env)?,
             ImplPrimitive::UnDatetime => env.monadic_ref_env(Value::undatetime)?,
             ImplPrimitive::ProgressiveIndexOf => env.dyadic_rr_env(Value::progressive_index_of)?,
@@ -1390,7 +1396,7 @@ impl ImplPrimitive {
                     )
                     .into());
                 }
-                env.rt.test_results.push(res);
+                env.rt.test_results.push((env.span(), res));
             }
         }
         Ok(())
@@ -1663,8 +1669,12 @@ impl PrimDoc {
                 PrimDocFragment::Primitive { .. } => {}
             }
         }
+        Cow::Owned(Self::fragments_text(&self.short))
+    }
+    /// Render documentation fragments as plain text
+    pub fn fragments_text(frags: &[PrimDocFragment]) -> String {
         let mut s = String::new();
-        for frag in &self.short {
+        for frag in frags {
             match frag {
                 PrimDocFragment::Text(t) => s.push_str(t),
                 PrimDocFragment::Code(c) => s.push_str(c),
@@ -1682,7 +1692,7 @@ impl PrimDoc {
                 }
             }
         }
-        Cow::Owned(s)
+        s
     }
     pub(crate) fn from_lines(s: &str) -> Self {
         let mut short = Vec::new();
@@ -1935,7 +1945,8 @@ mod tests {
                 if let PrimDocLine::Example(ex) = line {
                     if [
                         "&sl", "&tcpc", "&tlsc", "&ast", "&clip", "&fo", "&fc", "&fde", "&ftr",
-                        "&fld", "&fif", "&fras", "&frab", "&fmd", "timezone",
+                        "&fld", "&fglob", "&fif", "&fst", "&fras", "&frab", "&fmd", "&frn", "&fcp",
+                        "&httpg", "timezone",
                     ]
                     .iter()
                     .any(|prim| ex.input.contains(prim))
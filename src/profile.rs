@@ -23,13 +23,47 @@ pub fn run_profile() {
     enabled::run_profile();
 }
 
+/// Start recording profile scopes for [`write_folded_stacks`]
+///
+/// Returns `false` if the `profile` feature is not enabled, in which case nothing is recorded.
+pub fn start_recording() -> bool {
+    #[cfg(feature = "profile")]
+    enabled::start_recording();
+    cfg!(feature = "profile")
+}
+
+/// Write the profile scopes recorded since [`start_recording`] to a file
+///
+/// Profiling is done by instrumentation rather than sampling.
+/// Every profile scope entered in the interpreter is timed,
+/// and time spent in nested scopes is subtracted from the enclosing scope.
+///
+/// The output uses the folded stack format understood by `flamegraph.pl` and `inferno`.
+/// Each line is a `;`-separated stack of scope names followed by a space and the
+/// number of microseconds spent in the innermost scope, summed across all calls.
+pub fn write_folded_stacks(path: &std::path::Path) -> std::io::Result<()> {
+    #[cfg(feature = "profile")]
+    {
+        std::fs::write(path, enabled::folded_stacks())
+    }
+    #[cfg(not(feature = "profile"))]
+    {
+        _ = path;
+        Ok(())
+    }
+}
+
 #[cfg(feature = "profile")]
 pub(crate) mod enabled {
     use std::{
-        collections::HashMap,
+        cell::RefCell,
+        collections::{BTreeMap, HashMap},
         fs,
         io::{stdout, Write},
-        sync::OnceLock,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            OnceLock,
+        },
         time::Instant,
     };
 
@@ -166,6 +200,12 @@ Life ← ↥⊙↧∩=3,2-,/+≡↻☇1-1⇡3_3¤.
 
     impl Scope {
         pub fn new(name: &'static str) -> Self {
+            STACK.with(|stack| {
+                stack.borrow_mut().push(Frame {
+                    name,
+                    child_dur: 0.0,
+                })
+            });
             Scope {
                 name,
                 start: Instant::now(),
@@ -176,14 +216,46 @@ Life ← ↥⊙↧∩=3,2-,/+≡↻☇1-1⇡3_3¤.
     struct FinishedScope {
         name: &'static str,
         dur: f64,
+        /// The time not spent in nested scopes
+        self_dur: f64,
+        /// The names of the enclosing scopes and this one, if recording stacks
+        stack: Option<String>,
+    }
+
+    /// A scope that has not finished yet
+    struct Frame {
+        name: &'static str,
+        child_dur: f64,
+    }
+
+    thread_local! {
+        static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
     }
 
     impl Drop for Scope {
         fn drop(&mut self) {
             let end = Instant::now();
+            let dur = (end - self.start).as_secs_f64();
+            let (self_dur, stack) = STACK.with(|stack| {
+                let mut stack = stack.borrow_mut();
+                let frame = stack.pop();
+                let child_dur = frame.map_or(0.0, |frame| frame.child_dur);
+                let names = RECORD_STACKS.load(Ordering::Relaxed).then(|| {
+                    (stack.iter().map(|frame| frame.name))
+                        .chain([self.name])
+                        .collect::<Vec<_>>()
+                        .join(";")
+                });
+                if let Some(parent) = stack.last_mut() {
+                    parent.child_dur += dur;
+                }
+                (dur - child_dur, names)
+            });
             let finished = FinishedScope {
                 name: self.name,
-                dur: (end - self.start).as_secs_f64(),
+                dur,
+                self_dur,
+                stack,
             };
             if let Some(send) = SEND.get() {
                 send.send(finished).unwrap();
@@ -193,6 +265,7 @@ Life ← ↥⊙↧∩=3,2-,/+≡↻☇1-1⇡3_3¤.
 
     static SEND: OnceLock<Sender<FinishedScope>> = OnceLock::new();
     static RECV: OnceLock<Receiver<FinishedScope>> = OnceLock::new();
+    static RECORD_STACKS: AtomicBool = AtomicBool::new(false);
 
     fn init_profiler() {
         let (send, recv) = crossbeam_channel::unbounded();
@@ -200,6 +273,30 @@ Life ← ↥⊙↧∩=3,2-,/+≡↻☇1-1⇡3_3¤.
         RECV.set(recv).unwrap();
     }
 
+    pub fn start_recording() {
+        RECORD_STACKS.store(true, Ordering::Relaxed);
+        if SEND.get().is_none() {
+            init_profiler();
+        }
+    }
+
+    pub fn folded_stacks() -> String {
+        let mut stacks = BTreeMap::new();
+        for scope in RECV.get().into_iter().flat_map(|recv| recv.try_iter()) {
+            if let Some(stack) = scope.stack {
+                *stacks.entry(stack).or_insert(0.0) += scope.self_dur;
+            }
+        }
+        let mut folded = String::new();
+        for (stack, dur) in stacks {
+            let micros = (dur * 1e6).round() as u64;
+            if micros > 0 {
+                folded.push_str(&format!("{stack} {micros}\n"));
+            }
+        }
+        folded
+    }
+
     fn end_profiler() {
         let mut times = HashMap::new();
         for scope in RECV.get().unwrap().try_iter() {
@@ -10,7 +10,10 @@ use std::{
     panic::{catch_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
     str::FromStr,
-    sync::Arc,
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc,
+    },
     time::Duration,
 };
 
@@ -65,10 +68,20 @@ pub(crate) struct Runtime {
     pub(crate) execution_start: f64,
     /// Whether the program was interrupted
     pub(crate) interrupted: Option<Arc<dyn Fn() -> bool + Send + Sync>>,
+    /// The flag set by an [`InterruptHandle`]
+    interrupt_flag: Arc<AtomicBool>,
+    /// A limit on the size of a single array in bytes
+    pub(crate) max_memory: Option<usize>,
+    /// A limit on the size of an HTTP response in bytes
+    pub(crate) http_max_bytes: u64,
     /// Whether to print the time taken to execute each instruction
     time_instrs: bool,
     /// The time at which the last instruction was executed
     last_time: f64,
+    /// The total time spent executing each primitive, if it is being recorded
+    instr_timings: Option<HashMap<Primitive, f64>>,
+    /// For each primitive being timed, the time spent in primitives it called
+    nested_instr_times: Vec<f64>,
     /// Arguments passed from the command line
     cli_arguments: Vec<String>,
     /// File that was passed to the interpreter for execution
@@ -82,7 +95,11 @@ pub(crate) struct Runtime {
     /// Memoized values
     pub(crate) memo: Arc<ThreadLocal<RefCell<MemoMap>>>,
     /// The results of tests
-    pub(crate) test_results: Vec<UiuaResult>,
+    pub(crate) test_results: Vec<(Span, UiuaResult)>,
+    /// The results of tests that have finished running
+    pub(crate) finished_tests: Vec<TestResult>,
+    /// The stack as it was when the last run failed
+    pub(crate) error_stack: Vec<Value>,
     /// Reports to print
     pub(crate) reports: Vec<Report>,
 }
@@ -142,12 +159,19 @@ impl Default for ThisThread {
 #[derive(Debug, Clone)]
 struct Thread {
     #[cfg(not(target_arch = "wasm32"))]
-    pub recv: Receiver<UiuaResult<Vec<Value>>>,
+    pub recv: Receiver<UiuaResult<ThreadResult>>,
     #[cfg(target_arch = "wasm32")]
-    pub result: UiuaResult<Vec<Value>>,
+    pub result: UiuaResult<ThreadResult>,
     pub channel: Channel,
 }
 
+/// What a finished thread hands back to its parent
+#[derive(Debug, Clone)]
+struct ThreadResult {
+    stack: Vec<Value>,
+    instr_timings: Option<HashMap<Primitive, f64>>,
+}
+
 impl Default for Uiua {
     fn default() -> Self {
         Self::with_safe_sys()
@@ -166,6 +190,8 @@ pub enum RunMode {
     Test,
     /// Run everything
     All,
+    /// Like [`RunMode::Normal`], but the program is run repeatedly to measure its performance
+    Bench,
 }
 
 impl FromStr for RunMode {
@@ -175,11 +201,15 @@ impl FromStr for RunMode {
             "normal" => Ok(RunMode::Normal),
             "test" => Ok(RunMode::Test),
             "all" => Ok(RunMode::All),
+            "bench" => Ok(RunMode::Bench),
             _ => Err(format!("unknown run mode `{}`", s)),
         }
     }
 }
 
+/// The default limit on the size of an HTTP response in bytes
+const DEFAULT_HTTP_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
 impl Default for Runtime {
     fn default() -> Self {
         Runtime {
@@ -203,20 +233,51 @@ impl Default for Runtime {
             backend: Arc::new(SafeSys::default()),
             time_instrs: false,
             last_time: 0.0,
+            instr_timings: None,
+            nested_instr_times: Vec::new(),
             cli_arguments: Vec::new(),
             cli_file_path: PathBuf::new(),
             execution_limit: None,
             execution_start: 0.0,
             interrupted: None,
+            interrupt_flag: Arc::new(AtomicBool::new(false)),
+            max_memory: None,
+            http_max_bytes: DEFAULT_HTTP_MAX_BYTES,
             thread: ThisThread::default(),
             output_comments: HashMap::new(),
             memo: Arc::new(ThreadLocal::new()),
             test_results: Vec::new(),
+            finished_tests: Vec::new(),
+            error_stack: Vec::new(),
             reports: Vec::new(),
         }
     }
 }
 
+/// A handle that can interrupt a [`Uiua`] runtime from another thread
+///
+/// Get one with [`Uiua::interrupt_handle`].
+/// The interrupt is checked between instructions, so a single long-running primitive will finish before the program stops.
+/// An interrupt stops every thread of the current run. Interrupts made while nothing is running are ignored.
+#[derive(Debug, Clone)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Request that the current run stop with an error
+    pub fn interrupt(&self) {
+        self.0.store(true, atomic::Ordering::Relaxed);
+    }
+}
+
+/// The result of a single test assertion
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    /// The span of the assertion
+    pub span: Span,
+    /// The failure message, if the assertion failed
+    pub failure: Option<String>,
+}
+
 impl Uiua {
     /// Create a new Uiua runtime with the standard IO backend
     #[cfg(feature = "native_sys")]
@@ -261,6 +322,16 @@ impl Uiua {
     pub fn take_reports(&mut self) -> Vec<Report> {
         take(&mut self.rt.reports)
     }
+    /// Take the results of all finished test assertions
+    pub fn take_test_results(&mut self) -> Vec<TestResult> {
+        take(&mut self.rt.finished_tests)
+    }
+    /// Take the stack as it was when the last run failed
+    ///
+    /// The arguments of the function that failed may already have been popped.
+    pub fn take_error_stack(&mut self) -> Vec<Value> {
+        take(&mut self.rt.error_stack)
+    }
     /// Print all pending reports
     pub fn print_reports(&mut self) {
         for report in self.take_reports() {
@@ -276,6 +347,26 @@ impl Uiua {
         self.rt.time_instrs = time_instrs;
         self
     }
+    /// Set whether to record the total time spent executing each primitive
+    ///
+    /// The timings can be retrieved with [`Uiua::instr_timings`]
+    pub fn record_instr_timings(mut self, record: bool) -> Self {
+        self.rt.instr_timings = record.then(HashMap::new);
+        self
+    }
+    /// Get the total time spent executing each primitive, from longest to shortest
+    ///
+    /// A primitive's time does not include time spent in other primitives it calls,
+    /// so a modifier is only charged for its own overhead.
+    /// Time spent in threads is included once they are waited on.
+    /// This is empty unless recording was enabled with [`Uiua::record_instr_timings`].
+    pub fn instr_timings(&self) -> Vec<(Primitive, Duration)> {
+        let mut timings: Vec<_> = (self.rt.instr_timings.iter().flatten())
+            .map(|(prim, secs)| (*prim, Duration::from_secs_f64(secs.max(0.0))))
+            .collect();
+        timings.sort_by(|(_, a), (_, b)| b.cmp(a));
+        timings
+    }
     /// Limit the execution duration
     pub fn with_execution_limit(mut self, limit: Duration) -> Self {
         self.rt.execution_limit = Some(limit.as_secs_f64());
@@ -286,11 +377,31 @@ impl Uiua {
         self.rt.execution_limit = limit.map(|limit| limit.as_secs_f64());
         self
     }
+    /// Limit the size of arrays in bytes
+    ///
+    /// This is checked when arrays are allocated by operations that may create very large arrays, such as reshaping.
+    /// It is approximate, but it turns what would be an out-of-memory crash into an error.
+    /// The limit applies to each array separately, and it can only lower the built-in limit.
+    pub fn with_max_memory(mut self, bytes: usize) -> Self {
+        self.rt.max_memory = Some(bytes);
+        self
+    }
+    /// Limit the size of HTTP responses in bytes
+    ///
+    /// Defaults to 256 MiB
+    pub fn with_http_max_bytes(mut self, bytes: u64) -> Self {
+        self.rt.http_max_bytes = bytes;
+        self
+    }
     /// Set the interrupted hook
     pub fn with_interrupt_hook(mut self, hook: impl Fn() -> bool + Send + Sync + 'static) -> Self {
         self.rt.interrupted = Some(Arc::new(hook));
         self
     }
+    /// Get a handle that can interrupt the running program from another thread
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        InterruptHandle(self.rt.interrupt_flag.clone())
+    }
     /// Set the command line arguments
     pub fn with_args(mut self, args: Vec<String>) -> Self {
         self.rt.cli_arguments = args;
@@ -327,6 +438,15 @@ impl Uiua {
     pub fn run_str(&mut self, input: &str) -> UiuaResult<Compiler> {
         self.compile_run(|comp| comp.load_str(input))
     }
+    /// Evaluate a string as Uiua code and return the resulting stack
+    ///
+    /// The code is run with a [`SafeSys`] backend, so it cannot access the filesystem or network.
+    /// System functions that need those capabilities, such as [`SysOp::FReadAllStr`], will error.
+    pub fn eval_str(code: &str) -> UiuaResult<Vec<Value>> {
+        let mut env = Uiua::with_safe_sys();
+        env.run_str(code)?;
+        Ok(env.take_stack())
+    }
     /// Run a file as Uiua code
     pub fn run_file<P: AsRef<Path>>(&mut self, path: P) -> UiuaResult<Compiler> {
         self.compile_run(|comp| comp.load_file(path))
@@ -356,6 +476,8 @@ impl Uiua {
         fn run_asm(env: &mut Uiua, asm: Assembly) -> UiuaResult {
             env.asm = asm;
             env.rt.execution_start = env.rt.backend.now();
+            // Interrupts from before this run started do not apply to it
+            (env.rt.interrupt_flag).store(false, atomic::Ordering::Relaxed);
             let mut res = env.run_top_slices();
             let mut push_error = |te: UiuaError| match &mut res {
                 Ok(()) => res = Err(te),
@@ -364,7 +486,9 @@ impl Uiua {
             if !env.rt.test_results.is_empty() {
                 let total = env.rt.test_results.len();
                 let mut successes = 0;
-                for res in env.rt.test_results.drain(..) {
+                for (span, res) in env.rt.test_results.drain(..) {
+                    let failure = res.as_ref().err().map(|e| e.to_string());
+                    env.rt.finished_tests.push(TestResult { span, failure });
                     match res {
                         Ok(()) => successes += 1,
                         Err(e) => push_error(e),
@@ -376,8 +500,14 @@ impl Uiua {
                 env.rt = Runtime {
                     backend: env.rt.backend.clone(),
                     execution_limit: env.rt.execution_limit,
+                    interrupt_flag: env.rt.interrupt_flag.clone(),
+                    max_memory: env.rt.max_memory,
+                    http_max_bytes: env.rt.http_max_bytes,
                     time_instrs: env.rt.time_instrs,
+                    instr_timings: take(&mut env.rt.instr_timings),
                     output_comments: take(&mut env.rt.output_comments),
+                    finished_tests: take(&mut env.rt.finished_tests),
+                    error_stack: take(&mut env.rt.stack),
                     reports: take(&mut env.rt.reports),
                     ..Runtime::default()
                 };
@@ -466,10 +596,17 @@ code:
                 formatted_instr = format!("{instr:?}");
                 self.rt.last_time = self.rt.backend.now();
             }
+            let timed_prim = match instr {
+                &Instr::Prim(prim, _) if self.rt.instr_timings.is_some() => {
+                    self.rt.nested_instr_times.push(0.0);
+                    Some((prim, self.rt.backend.now()))
+                }
+                _ => None,
+            };
             let res = match instr {
                 Instr::Comment(_) => Ok(()),
-                // Pause execution timer during &sc
-                &Instr::Prim(prim @ Primitive::Sys(SysOp::ScanLine), span) => {
+                // Pause execution timer during &sc and &scl
+                &Instr::Prim(prim @ Primitive::Sys(SysOp::ScanLine | SysOp::ScanLines), span) => {
                     self.with_prim_span(span, Some(prim), |env| {
                         let start = env.rt.backend.now();
                         let res = prim.run(env);
@@ -736,6 +873,17 @@ code:
                 );
                 self.rt.last_time = self.rt.backend.now();
             }
+            if let Some((prim, start)) = timed_prim {
+                let elapsed = self.rt.backend.now() - start;
+                // Only record the time not spent in other primitives
+                let nested = self.rt.nested_instr_times.pop().unwrap_or(0.0);
+                if let Some(outer) = self.rt.nested_instr_times.last_mut() {
+                    *outer += elapsed;
+                }
+                if let Some(timings) = &mut self.rt.instr_timings {
+                    *timings.entry(prim).or_default() += elapsed - nested;
+                }
+            }
             if let Err(mut err) = res {
                 // Trace errors
                 let frame = self.rt.call_stack.pop().unwrap();
@@ -768,6 +916,10 @@ code:
                 return Err(UiuaErrorKind::Interrupted.into());
             }
         }
+        // The flag is only loaded so that every thread of the program sees it
+        if self.rt.interrupt_flag.load(atomic::Ordering::Relaxed) {
+            return Err(UiuaErrorKind::Interrupted.into());
+        }
         Ok(())
     }
     pub(crate) fn with_span<T>(
@@ -1442,15 +1594,22 @@ code:
                 call_stack: Vec::new(),
                 time_instrs: self.rt.time_instrs,
                 last_time: self.rt.last_time,
+                instr_timings: self.rt.instr_timings.as_ref().map(|_| HashMap::new()),
+                nested_instr_times: Vec::new(),
                 cli_arguments: self.rt.cli_arguments.clone(),
                 cli_file_path: self.rt.cli_file_path.clone(),
                 backend: self.rt.backend.clone(),
                 execution_limit: self.rt.execution_limit,
                 execution_start: self.rt.execution_start,
                 interrupted: self.rt.interrupted.clone(),
+                interrupt_flag: self.rt.interrupt_flag.clone(),
+                max_memory: self.rt.max_memory,
+                http_max_bytes: self.rt.http_max_bytes,
                 output_comments: HashMap::new(),
                 memo: self.rt.memo.clone(),
                 test_results: Vec::new(),
+                finished_tests: Vec::new(),
+                error_stack: Vec::new(),
                 reports: Vec::new(),
                 thread,
             },
@@ -1459,16 +1618,16 @@ code:
         let recv = {
             let (send, recv) = crossbeam_channel::unbounded();
             if _pool {
-                rayon::spawn(move || _ = send.send(f(&mut env).map(|_| env.take_stack())));
+                rayon::spawn(move || _ = send.send(f(&mut env).map(|_| env.finish_thread())));
             } else {
                 std::thread::Builder::new()
-                    .spawn(move || _ = send.send(f(&mut env).map(|_| env.take_stack())))
+                    .spawn(move || _ = send.send(f(&mut env).map(|_| env.finish_thread())))
                     .map_err(|e| self.error(format!("Error spawning thread: {e}")))?;
             }
             recv
         };
         #[cfg(target_arch = "wasm32")]
-        let result = f(&mut env).map(|_| env.take_stack());
+        let result = f(&mut env).map(|_| env.finish_thread());
 
         let id = self.rt.thread.next_child_id;
         self.rt.thread.next_child_id += 1;
@@ -1492,47 +1651,12 @@ code:
     pub(crate) fn wait(&mut self, id: Value) -> UiuaResult {
         let ids = id.as_natural_array(self, "Thread id must be an array of natural numbers")?;
         if ids.shape.is_empty() {
-            let handle = ids.data[0];
-            #[cfg(not(target_arch = "wasm32"))]
-            let thread_stack = self
-                .rt
-                .thread
-                .children
-                .remove(&handle)
-                .ok_or_else(|| self.error("Invalid thread id"))?
-                .recv
-                .recv()
-                .unwrap()?;
-            #[cfg(target_arch = "wasm32")]
-            let thread_stack = self
-                .rt
-                .thread
-                .children
-                .remove(&handle)
-                .ok_or_else(|| self.error("Invalid thread id"))?
-                .result?;
+            let thread_stack = self.join_thread(ids.data[0])?;
             self.rt.stack.extend(thread_stack);
         } else {
             let mut rows = Vec::new();
             for handle in ids.data {
-                #[cfg(not(target_arch = "wasm32"))]
-                let thread_stack = self
-                    .rt
-                    .thread
-                    .children
-                    .remove(&handle)
-                    .ok_or_else(|| self.error("Invalid thread id"))?
-                    .recv
-                    .recv()
-                    .unwrap()?;
-                #[cfg(target_arch = "wasm32")]
-                let thread_stack = self
-                    .rt
-                    .thread
-                    .children
-                    .remove(&handle)
-                    .ok_or_else(|| self.error("Invalid thread id"))?
-                    .result?;
+                let thread_stack = self.join_thread(handle)?;
                 let row = if thread_stack.len() == 1 {
                     thread_stack.into_iter().next().unwrap()
                 } else {
@@ -1548,6 +1672,32 @@ code:
         }
         Ok(())
     }
+    /// Wait for a child thread to finish and take its stack
+    ///
+    /// The thread's instruction timings are merged into this thread's
+    fn join_thread(&mut self, handle: usize) -> UiuaResult<Vec<Value>> {
+        let thread = (self.rt.thread.children.remove(&handle))
+            .ok_or_else(|| self.error("Invalid thread id"))?;
+        #[cfg(not(target_arch = "wasm32"))]
+        let result = thread.recv.recv().unwrap()?;
+        #[cfg(target_arch = "wasm32")]
+        let result = thread.result?;
+        if let Some((timings, thread_timings)) =
+            (self.rt.instr_timings.as_mut()).zip(result.instr_timings)
+        {
+            for (prim, time) in thread_timings {
+                *timings.entry(prim).or_default() += time;
+            }
+        }
+        Ok(result.stack)
+    }
+    /// Get the results of a thread that has finished running
+    fn finish_thread(&mut self) -> ThreadResult {
+        ThreadResult {
+            stack: self.take_stack(),
+            instr_timings: take(&mut self.rt.instr_timings),
+        }
+    }
     pub(crate) fn send(&self, id: Value, value: Value) -> UiuaResult {
         if cfg!(target_arch = "wasm32") {
             return Err(self.error("send is not supported in this environment"));
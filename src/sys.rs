@@ -1,5 +1,6 @@
 use std::{
     any::Any,
+    collections::{BTreeMap, BTreeSet},
     fmt,
     mem::take,
     net::SocketAddr,
@@ -17,8 +18,10 @@ use serde::*;
 use time::UtcOffset;
 
 use crate::{
-    algorithm::validate_size, cowslice::cowslice, primitive::PrimDoc, Array, Boxed, FfiType,
-    Purity, Signature, Uiua, UiuaResult, Value,
+    algorithm::validate_size,
+    cowslice::{cowslice, CowSlice},
+    primitive::PrimDoc,
+    Array, Boxed, FfiType, Purity, Signature, Uiua, UiuaResult, Value,
 };
 
 /// The text of Uiua's example module
@@ -166,16 +169,43 @@ sys_op! {
     (1(0), Prin, StdIO, "&pf", "print and flush", Mutating),
     /// Print a value to stdout followed by a newline
     (1(0), Print, StdIO, "&p", "print with newline", Mutating),
+    /// Flush stdout
+    ///
+    /// This is only necessary when output is buffered, such as with `uiua run --buffered-output`.
+    /// Buffered output is also flushed before reading from stdin, before printing to stderr, and when the program ends.
+    (0(0), Flush, StdIO, "&flush", "flush stdout", Mutating),
+    /// Print a value to stdout with a color or style
+    ///
+    /// Expects a style and a value.
+    /// The style is a string of space-separated words.
+    /// It may contain a color (`black`, `red`, `green`, `yellow`, `blue`, `magenta`, `cyan`, or `white`) and `bold`.
+    /// Like [&pf], no newline is printed.
+    /// ex: &ps "bold red" "Error!"
+    /// Environments that do not support styled output print the value without styling.
+    (2(0), PrintStyled, StdIO, "&ps", "print styled", Mutating),
     /// Read a line from stdin
     ///
     /// The normal output is a string.
     /// If EOF is reached, the number `0` is returned instead.
     /// Programs that wish to properly handle EOF should check for this.
     (0, ScanLine, StdIO, "&sc", "scan line", Mutating),
+    /// Read all remaining lines from stdin
+    ///
+    /// The output is a list of boxed strings.
+    /// Reading continues from wherever previous calls to [&sc] left off.
+    /// If EOF has already been reached, the list is empty.
+    (0, ScanLines, StdIO, "&scl", "scan lines", Mutating),
+    /// Ring the terminal bell
+    ///
+    /// In the native interpreter, this prints the BEL character to stdout.
+    /// Environments without a terminal ignore it.
+    /// Combine with [&sl] for a simple alarm.
+    (0(0), Bell, StdIO, "&bel", "bell", Mutating),
     /// Get the size of the terminal
     ///
     /// The result is a 2-element array of the height and width of the terminal.
     /// Height comes first so that the array can be used as a shape in [reshape].
+    /// If the size cannot be determined, such as when output is piped, the native interpreter returns `[24 80]`.
     (0, TermSize, Env, "&ts", "terminal size", Mutating),
     /// Exit the program with a status code
     (1(0), Exit, Misc, "&exit", "exit", Mutating),
@@ -199,6 +229,12 @@ sys_op! {
     /// Expects a string and returns a string.
     /// If the environment variable does not exist, an error is thrown.
     (1, Var, Env, "&var", "environment variable"),
+    /// Get all environment variables
+    ///
+    /// The result is a rank-`2` array of boxed strings.
+    /// Each row contains a variable's name and its value.
+    /// The order of the rows is not guaranteed.
+    (0, Vars, Env, "&vars", "environment variables"),
     /// Run a command and wait for it to finish
     ///
     /// Standard IO will be inherited. Returns the exit code of the command.
@@ -220,8 +256,22 @@ sys_op! {
     /// Using [&cl] on *all 3* handles will kill the child process.
     /// [under][&runs] calls [&cl] on all 3 streams automatically.
     (1(3), RunStream, Command, "&runs", "run command stream", Mutating),
+    /// Run a Uiua file and capture its stack
+    ///
+    /// Expects a path to a Uiua file.
+    /// The file is run from scratch in a new interpreter with the same system backend.
+    /// Unlike imports, the result is not cached, so the file is run every time.
+    /// Returns the resulting stack as a list of [box]es, with the bottom of the stack first.
+    (1, RunFile, Command, "&runf", "run file", Mutating),
     /// Change the current directory
+    ///
+    /// This affects how relative paths are resolved by subsequent file operations.
     (1(0), ChangeDirectory, Filesystem, "&cd", "change directory", Mutating),
+    /// Get the current directory
+    ///
+    /// Relative paths given to file operations are resolved against this directory.
+    /// It can be changed with [&cd].
+    (0, CurrentDirectory, Filesystem, "&cwd", "current directory"),
     /// Get the contents of the clipboard
     ///
     /// Returns a string of the clipboard's contents.
@@ -242,6 +292,7 @@ sys_op! {
     /// ex: &rs 4 &fo "example.txt"
     /// Using [infinity] as the count will read until the end of the stream.
     /// ex: &rs ∞ &fo "example.txt"
+    /// `&rs ∞ 0` reads all of stdin.
     ///
     /// [&rs] will attempt to read the given number of *bytes* from the stream.
     /// If the read bytes are not valid UTF-8, up to 3 additional bytes will be read in an attempt to finish a valid UTF-8 character.
@@ -265,6 +316,17 @@ sys_op! {
     /// The stream handle `0` is stdin.
     /// ex: &ru "Uiua" &fo "example.txt"
     (2, ReadUntil, Stream, "&ru", "read until", Mutating),
+    /// Read a line from a stream
+    ///
+    /// Expects a stream handle.
+    /// The normal output is a string without the trailing newline.
+    /// If the end of the stream is reached, the number `0` is returned instead.
+    /// This allows large files to be processed one line at a time.
+    /// The stream handle `0` is stdin.
+    /// ex: &rl &fo "example.txt"
+    ///
+    /// See also: [&ru], [&sc]
+    (1, ReadLine, Stream, "&rl", "read line", Mutating),
     /// Write an array to a stream
     ///
     /// If the stream is a file, the file may not be written to until it is closed with [&cl].
@@ -278,11 +340,12 @@ sys_op! {
     /// Close a stream by its handle
     ///
     /// This will close files, tcp listeners, and tcp sockets.
+    /// Using a handle after it has been closed is an error.
     (1(0), Close, Stream, "&cl", "close handle", Mutating),
     /// Open a file and return a handle to it
     ///
     /// ex: &fo "example.txt"
-    /// The file can be read from with [&rs], [&rb], or [&ru].
+    /// The file can be read from with [&rs], [&rb], [&ru], or [&rl].
     /// The file can be written to with [&w].
     /// In some cases, the file may not be actually written to until it is closed with [&cl].
     /// [under][&fo] calls [&cl] automatically.
@@ -302,17 +365,35 @@ sys_op! {
     (1(0), FMakeDir, Filesystem, "&fmd", "file - make directory", Mutating),
     /// Delete a file or directory
     ///
-    /// ex: &fde "example.txt"
+    /// ex: &fwa "delete.txt" "Goodbye"
+    ///   : &fde "delete.txt"
     /// Deletes the file or directory at the given path.
+    /// Directories are deleted recursively.
+    /// If nothing exists at the path, an error is thrown.
     /// Be careful with this function, as deleted files and directories cannot be recovered!
     /// For a safer alternative, see [&ftr].
     (1(0), FDelete, Filesystem, "&fde", "file - delete", Mutating),
     /// Move a file or directory to the trash
     ///
-    /// ex: &ftr "example.txt"
+    /// ex: &fwa "trash.txt" "Goodbye"
+    ///   : &ftr "trash.txt"
     /// Moves the file or directory at the given path to the trash.
     /// This is a safer alternative to [&fde].
     (1(0), FTrash, Filesystem, "&ftr", "file - trash", Mutating),
+    /// Rename or move a file or directory
+    ///
+    /// Expects a source path and a destination path.
+    /// ex: &fwa "old.txt" "Hello"
+    ///   : &frn "old.txt" "new.txt"
+    ///   : &fras "new.txt"
+    /// If the destination is on a different filesystem, the file is copied and then the original is deleted.
+    (2(0), FRename, Filesystem, "&frn", "file - rename", Mutating),
+    /// Copy a file
+    ///
+    /// Expects a source path and a destination path.
+    /// ex: &fcp "example.txt" "copy.txt"
+    /// The destination will be overwritten if it exists.
+    (2(0), FCopy, Filesystem, "&fcp", "file - copy", Mutating),
     /// Check if a file, directory, or symlink exists at a path
     ///
     /// ex: &fe "example.txt"
@@ -323,10 +404,23 @@ sys_op! {
     /// The result is a list of boxed strings.
     /// ex: &fld "."
     (1, FListDir, Filesystem, "&fld", "file - list directory"),
+    /// Find all paths matching a glob pattern
+    ///
+    /// Supports `*`, `?`, `[...]`, and `**` for matching any number of directories.
+    /// Returns a list of [box]ed paths in sorted order.
+    /// ex: &fglob "*.txt"
+    (1, FGlob, Filesystem, "&fglob", "file - glob"),
     /// Check if a path is a file
     ///
     /// ex: &fif "example.txt"
     (1, FIsFile, Filesystem, "&fif", "file - is file"),
+    /// Get the size, modification time, and kind of a file or directory
+    ///
+    /// Returns a list of `[size modified isdir]`.
+    /// `size` is in bytes. `modified` is in milliseconds since the Unix epoch.
+    /// ex: &fst "example.txt"
+    /// This is cheaper than reading a whole file just to get its size.
+    (1, FStat, Filesystem, "&fst", "file - stat"),
     /// Read all the contents of a file into a string
     ///
     /// Expects a path and returns a rank-`1` character array.
@@ -359,6 +453,16 @@ sys_op! {
     ///   : &fwa Path +@A⇡26
     ///   : &fras Path
     (2(0), FWriteAll, Filesystem, "&fwa", "file - write all", Mutating),
+    /// Append the entire contents of an array to a file
+    ///
+    /// Expects a path and a rank-`1` array of either numbers or characters.
+    /// The file will be created if it does not exist.
+    ///
+    /// ex: Path ← "test.txt"
+    ///   : &fap Path "Hello, "
+    ///   : &fap Path "World!"
+    ///   : &fras Path
+    (2(0), FAppend, Filesystem, "&fap", "file - append", Mutating),
     /// Show an image
     ///
     /// How the image is shown depends on the system backend.
@@ -378,6 +482,13 @@ sys_op! {
     ///
     /// See also: [img]
     (1(0), ImShow, Media, "&ims", "image - show", Mutating),
+    /// Check if a value can be shown or encoded as an image
+    ///
+    /// Returns `1` if the value is a rank 2 or 3 numeric array whose last axis, if it has one, is length 1, 2, 3, or 4, and `0` otherwise.
+    /// These are the same rules used by [&ims] and [img].
+    /// ex: &imv ↯10_10_3 0.5
+    /// ex: &imv ↯10_10_5 0
+    (1, ImValid, Media, "&imv", "image - valid"),
     /// Show a gif
     ///
     /// The first argument is a framerate in seconds.
@@ -491,6 +602,16 @@ sys_op! {
     /// - The HTTP version
     /// - The `Host` header (if not defined)
     (2, HttpsWrite, Tcp, "&httpsw", "https - Make an HTTP(S) request", Mutating),
+    /// Get the contents of a URL with an HTTP(S) GET request
+    ///
+    /// Expects a URL starting with `http://` or `https://`.
+    /// Returns the body of the response as a byte array.
+    /// ex: &httpg "https://example.com"
+    /// If the response status is not a success code, an error is thrown.
+    /// By default, responses larger than 256 MiB are also an error.
+    ///
+    /// For more control over the request, use [&httpsw].
+    (1, HttpGet, Tcp, "&httpg", "http - get", Mutating),
     /// Capture an image from a webcam
     ///
     /// Takes the index of the webcam to capture from.
@@ -708,16 +829,44 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     }
     /// Print a string that was create by `trace`
     fn print_str_trace(&self, s: &str) {}
+    /// Flush any buffered stdout
+    fn flush_stdout(&self) -> Result<(), String> {
+        Ok(())
+    }
+    /// Print a string (without a newline) to stdout with a style
+    ///
+    /// The default implementation ignores the style.
+    fn print_styled(&self, s: &str, style: TextStyle) -> Result<(), String> {
+        self.print_str_stdout(s)
+    }
+    /// Ring the terminal bell
+    fn bell(&self) -> Result<(), String> {
+        Ok(())
+    }
     /// Read a line from stdin
     ///
     /// Should return `Ok(None)` if EOF is reached.
     fn scan_line_stdin(&self) -> Result<Option<String>, String> {
         Err("Reading from stdin is not supported in this environment".into())
     }
+    /// Read all remaining lines from stdin
+    ///
+    /// Should return an empty list if EOF has already been reached.
+    fn scan_lines_stdin(&self) -> Result<Vec<String>, String> {
+        let mut lines = Vec::new();
+        while let Some(line) = self.scan_line_stdin()? {
+            lines.push(line);
+        }
+        Ok(lines)
+    }
     /// Read a number of bytes from stdin
     fn scan_stdin(&self, count: usize) -> Result<Vec<u8>, String> {
         Err("Reading from stdin is not supported in this environment".into())
     }
+    /// Read from stdin until the end
+    fn scan_all_stdin(&self) -> Result<Vec<u8>, String> {
+        Err("Reading from stdin is not supported in this environment".into())
+    }
     /// Read from stdin until a delimiter is reached
     fn scan_until_stdin(&self, delim: &[u8]) -> Result<Vec<u8>, String> {
         let mut buffer = Vec::new();
@@ -745,6 +894,10 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn var(&self, name: &str) -> Option<String> {
         None
     }
+    /// Get all environment variables as name-value pairs
+    fn vars(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
     /// Get the size of the terminal
     fn term_size(&self) -> Result<(usize, usize), String> {
         Err("Getting the terminal size is not supported in this environment".into())
@@ -761,10 +914,18 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn list_dir(&self, path: &str) -> Result<Vec<String>, String> {
         Err("Listing directories is not supported in this environment".into())
     }
+    /// Find all paths matching a glob pattern
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        Err("Globbing paths is not supported in this environment".into())
+    }
     /// Check if a path is a file
     fn is_file(&self, path: &str) -> Result<bool, String> {
         Err("Checking if a path is a file is not supported in this environment".into())
     }
+    /// Get the size in bytes, modification time in milliseconds since the Unix epoch, and whether a path is a directory
+    fn file_stat(&self, path: &str) -> Result<(u64, f64, bool), String> {
+        Err("Getting file metadata is not supported in this environment".into())
+    }
     /// Delete a file or directory
     fn delete(&self, path: &str) -> Result<(), String> {
         Err("Deleting files is not supported in this environment".into())
@@ -773,6 +934,14 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn trash(&self, path: &str) -> Result<(), String> {
         Err("Trashing files is not supported in this environment".into())
     }
+    /// Rename or move a file or directory
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        Err("Renaming files is not supported in this environment".into())
+    }
+    /// Copy a file
+    fn copy_file(&self, from: &str, to: &str) -> Result<(), String> {
+        Err("Copying files is not supported in this environment".into())
+    }
     /// Read at most `count` bytes from a stream
     fn read(&self, handle: Handle, count: usize) -> Result<Vec<u8>, String> {
         Err("Reading from streams is not supported in this environment".into())
@@ -826,6 +995,10 @@ pub trait SysBackend: Any + Send + Sync + 'static {
         self.close(handle)?;
         Ok(())
     }
+    /// Append all bytes to a file, creating it if it does not exist
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        Err("Appending to files is not supported in this environment".into())
+    }
     /// Get the clipboard contents
     fn clipboard(&self) -> Result<String, String> {
         Err("Getting the clipboard is not supported in this environment".into())
@@ -937,10 +1110,20 @@ pub trait SysBackend: Any + Send + Sync + 'static {
     fn change_directory(&self, path: &str) -> Result<(), String> {
         Err("Changing directories is not supported in this environment".into())
     }
+    /// Get the current directory
+    fn current_directory(&self) -> Result<String, String> {
+        Err("Getting the current directory is not supported in this environment".into())
+    }
     /// Make an HTTPS request on a TCP socket
     fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
         Err("Making HTTPS requests is not supported in this environment".into())
     }
+    /// Get the contents of a URL with an HTTP(S) GET request
+    ///
+    /// Responses larger than `max_bytes` should be an error
+    fn http_get(&self, url: &str, max_bytes: u64) -> Result<Vec<u8>, String> {
+        Err("Making HTTP requests is not supported in this environment".into())
+    }
     /// Capture an image from the webcam
     fn webcam_capture(&self, index: usize) -> Result<WebcamImage, String> {
         Err("Capturing from webcam is not supported in this environment".into())
@@ -996,6 +1179,58 @@ pub enum GitTarget {
     Commit(String),
 }
 
+/// A style for [`SysBackend::print_styled`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextStyle {
+    /// The foreground color
+    pub color: Option<TextColor>,
+    /// Whether the text is bold
+    pub bold: bool,
+}
+
+/// A foreground color for [`TextStyle`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum TextColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl TextStyle {
+    /// Parse a style from space-separated words, like `"bold red"`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut style = TextStyle::default();
+        for word in spec.split_whitespace() {
+            let color = match word.to_lowercase().as_str() {
+                "bold" => {
+                    style.bold = true;
+                    continue;
+                }
+                "black" => TextColor::Black,
+                "red" => TextColor::Red,
+                "green" => TextColor::Green,
+                "yellow" => TextColor::Yellow,
+                "blue" => TextColor::Blue,
+                "magenta" => TextColor::Magenta,
+                "cyan" => TextColor::Cyan,
+                "white" => TextColor::White,
+                _ => return Err(format!("Unknown style {word:?}")),
+            };
+            if style.color.is_some() {
+                return Err(format!("Style {spec:?} has multiple colors"));
+            }
+            style.color = Some(color);
+        }
+        Ok(style)
+    }
+}
+
 impl fmt::Debug for dyn SysBackend {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "<sys backend>")
@@ -1003,6 +1238,9 @@ impl fmt::Debug for dyn SysBackend {
 }
 
 /// A safe backend with no IO other than captured stdout and stderr
+///
+/// This is useful for hosts that want to display a program's output themselves.
+/// Install it with [`Uiua::with_backend`] and retrieve it with [`Uiua::downcast_backend`].
 #[derive(Default)]
 pub struct SafeSys {
     stdout: Arc<Mutex<Vec<u8>>>,
@@ -1038,6 +1276,257 @@ impl SafeSys {
     pub fn take_stderr(&self) -> Vec<u8> {
         take(&mut *self.stderr.lock())
     }
+    /// Take the captured stdout as a string
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn take_stdout_string(&self) -> String {
+        String::from_utf8_lossy(&self.take_stdout()).into_owned()
+    }
+    /// Take the captured stderr as a string
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn take_stderr_string(&self) -> String {
+        String::from_utf8_lossy(&self.take_stderr()).into_owned()
+    }
+}
+
+/// A backend with an in-memory filesystem
+///
+/// Like [`SafeSys`], stdout and stderr are captured.
+/// File operations act on in-memory files and directories, so programs that manipulate files can be tested without touching the disk.
+/// Seed it with [`MemSys::with_file`] and [`MemSys::with_dir`], and inspect it afterwards with [`MemSys::file`].
+#[derive(Default)]
+pub struct MemSys {
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    files: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+    dirs: Arc<Mutex<BTreeSet<String>>>,
+}
+
+/// Normalize a path for the in-memory filesystem
+fn mem_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    (path.split('/'))
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Get the parent of a normalized in-memory path
+fn mem_parent(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(parent, _)| parent)
+}
+
+impl MemSys {
+    /// Create a new in-memory backend with no files
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a file
+    ///
+    /// Parent directories are created as needed.
+    pub fn with_file(self, path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        let path = mem_path(path);
+        self.add_parents(&path);
+        self.files.lock().insert(path, contents.into());
+        self
+    }
+    /// Add a directory
+    ///
+    /// Parent directories are created as needed.
+    pub fn with_dir(self, path: &str) -> Self {
+        let path = mem_path(path);
+        self.add_parents(&path);
+        self.dirs.lock().insert(path);
+        self
+    }
+    /// Get the contents of a file
+    pub fn file(&self, path: &str) -> Option<Vec<u8>> {
+        self.files.lock().get(&mem_path(path)).cloned()
+    }
+    /// Get the paths of all files
+    pub fn file_paths(&self) -> Vec<String> {
+        self.files.lock().keys().cloned().collect()
+    }
+    /// Take the captured stdout as a string
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn take_stdout_string(&self) -> String {
+        String::from_utf8_lossy(&take(&mut *self.stdout.lock())).into_owned()
+    }
+    /// Take the captured stderr as a string
+    ///
+    /// Invalid UTF-8 is replaced with `U+FFFD`.
+    pub fn take_stderr_string(&self) -> String {
+        String::from_utf8_lossy(&take(&mut *self.stderr.lock())).into_owned()
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        path.is_empty() || self.dirs.lock().contains(path)
+    }
+    fn add_parents(&self, path: &str) {
+        let mut dirs = self.dirs.lock();
+        let mut parent = mem_parent(path);
+        while !parent.is_empty() {
+            dirs.insert(parent.into());
+            parent = mem_parent(parent);
+        }
+    }
+    fn check_parent(&self, path: &str) -> Result<(), String> {
+        let parent = mem_parent(path);
+        if self.is_dir(parent) {
+            Ok(())
+        } else {
+            Err(format!("Directory {parent} does not exist"))
+        }
+    }
+}
+
+impl SysBackend for MemSys {
+    fn any(&self) -> &dyn Any {
+        self
+    }
+    fn any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+    fn print_str_stdout(&self, s: &str) -> Result<(), String> {
+        self.stdout.lock().extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+    fn print_str_stderr(&self, s: &str) -> Result<(), String> {
+        self.stderr.lock().extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+    fn file_exists(&self, path: &str) -> bool {
+        let path = mem_path(path);
+        self.is_dir(&path) || self.files.lock().contains_key(&path)
+    }
+    fn list_dir(&self, path: &str) -> Result<Vec<String>, String> {
+        let dir = mem_path(path);
+        if !self.is_dir(&dir) {
+            return Err(format!("Directory {path} does not exist"));
+        }
+        let files = self.files.lock();
+        let dirs = self.dirs.lock();
+        Ok((files.keys().chain(dirs.iter()))
+            .filter(|child| mem_parent(child) == dir && !child.is_empty())
+            .cloned()
+            .collect())
+    }
+    fn is_file(&self, path: &str) -> Result<bool, String> {
+        let mem = mem_path(path);
+        if self.files.lock().contains_key(&mem) {
+            Ok(true)
+        } else if self.is_dir(&mem) {
+            Ok(false)
+        } else {
+            Err(format!("{path} does not exist"))
+        }
+    }
+    fn file_stat(&self, path: &str) -> Result<(u64, f64, bool), String> {
+        let mem = mem_path(path);
+        if let Some(contents) = self.files.lock().get(&mem) {
+            Ok((contents.len() as u64, 0.0, false))
+        } else if self.is_dir(&mem) {
+            Ok((0, 0.0, true))
+        } else {
+            Err(format!("{path} does not exist"))
+        }
+    }
+    fn delete(&self, path: &str) -> Result<(), String> {
+        let mem = mem_path(path);
+        if self.files.lock().remove(&mem).is_some() {
+            return Ok(());
+        }
+        if mem.is_empty() || !self.dirs.lock().remove(&mem) {
+            return Err(format!("{path} does not exist"));
+        }
+        let prefix = format!("{mem}/");
+        self.files
+            .lock()
+            .retain(|file, _| !file.starts_with(&prefix));
+        self.dirs.lock().retain(|dir| !dir.starts_with(&prefix));
+        Ok(())
+    }
+    fn trash(&self, path: &str) -> Result<(), String> {
+        self.delete(path)
+    }
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        let (from_mem, to_mem) = (mem_path(from), mem_path(to));
+        self.check_parent(&to_mem)?;
+        let mut files = self.files.lock();
+        if let Some(contents) = files.remove(&from_mem) {
+            files.insert(to_mem, contents);
+            return Ok(());
+        }
+        let mut dirs = self.dirs.lock();
+        if from_mem.is_empty() || !dirs.remove(&from_mem) {
+            return Err(format!("{from} does not exist"));
+        }
+        let prefix = format!("{from_mem}/");
+        let moved = |path: &String| format!("{to_mem}/{}", &path[prefix.len()..]);
+        let moved_files: Vec<_> = (files.keys())
+            .filter(|file| file.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for file in moved_files {
+            let contents = files.remove(&file).unwrap();
+            files.insert(moved(&file), contents);
+        }
+        let moved_dirs: Vec<_> = (dirs.iter())
+            .filter(|dir| dir.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for dir in moved_dirs {
+            dirs.remove(&dir);
+            dirs.insert(moved(&dir));
+        }
+        dirs.insert(to_mem);
+        Ok(())
+    }
+    fn copy_file(&self, from: &str, to: &str) -> Result<(), String> {
+        let to_mem = mem_path(to);
+        self.check_parent(&to_mem)?;
+        let mut files = self.files.lock();
+        let contents = (files.get(&mem_path(from)).cloned())
+            .ok_or_else(|| format!("File {from} does not exist"))?;
+        files.insert(to_mem, contents);
+        Ok(())
+    }
+    fn make_dir(&self, path: &Path) -> Result<(), String> {
+        let mem = mem_path(&path.to_string_lossy());
+        if self.files.lock().contains_key(&mem) {
+            return Err(format!("{} already exists as a file", path.display()));
+        }
+        self.add_parents(&mem);
+        self.dirs.lock().insert(mem);
+        Ok(())
+    }
+    fn file_read_all(&self, path: &Path) -> Result<Vec<u8>, String> {
+        (self
+            .files
+            .lock()
+            .get(&mem_path(&path.to_string_lossy()))
+            .cloned())
+        .ok_or_else(|| format!("File {} does not exist", path.display()))
+    }
+    fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        let mem = mem_path(&path.to_string_lossy());
+        if self.is_dir(&mem) {
+            return Err(format!("{} is a directory", path.display()));
+        }
+        self.check_parent(&mem)?;
+        self.files.lock().insert(mem, contents.to_vec());
+        Ok(())
+    }
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        let mem = mem_path(&path.to_string_lossy());
+        if self.is_dir(&mem) {
+            return Err(format!("{} is a directory", path.display()));
+        }
+        self.check_parent(&mem)?;
+        (self.files.lock().entry(mem).or_default()).extend_from_slice(contents);
+        Ok(())
+    }
 }
 
 /// Trait for converting to a system backend
@@ -1079,6 +1568,9 @@ impl SysOp {
                     .print_str_stdout(&val.format())
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::Flush => {
+                (env.rt.backend).flush_stdout().map_err(|e| env.error(e))?;
+            }
             SysOp::Print => {
                 let val = env.pop(1)?;
                 (env.rt.backend)
@@ -1088,6 +1580,14 @@ impl SysOp {
                     .print_str_stdout("\n")
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::PrintStyled => {
+                let style = env.pop(1)?.as_string(env, "Style must be a string")?;
+                let style = TextStyle::parse(&style).map_err(|e| env.error(e))?;
+                let val = env.pop(2)?;
+                (env.rt.backend)
+                    .print_styled(&val.format(), style)
+                    .map_err(|e| env.error(e))?;
+            }
             SysOp::ScanLine => {
                 if let Some(line) = env.rt.backend.scan_line_stdin().map_err(|e| env.error(e))? {
                     env.push(line);
@@ -1095,6 +1595,15 @@ impl SysOp {
                     env.push(0u8);
                 }
             }
+            SysOp::ScanLines => {
+                let lines = env
+                    .rt
+                    .backend
+                    .scan_lines_stdin()
+                    .map_err(|e| env.error(e))?;
+                env.push(Array::<Boxed>::from_iter(lines));
+            }
+            SysOp::Bell => env.rt.backend.bell().map_err(|e| env.error(e))?,
             SysOp::TermSize => {
                 let (width, height) = env.rt.backend.term_size().map_err(|e| env.error(e))?;
                 env.push(cowslice![height as f64, width as f64])
@@ -1125,6 +1634,15 @@ impl SysOp {
                     })?;
                 env.push(var);
             }
+            SysOp::Vars => {
+                let vars = env.rt.backend.vars();
+                let shape = [vars.len(), 2];
+                let data: CowSlice<Boxed> = (vars.into_iter())
+                    .flat_map(|(name, value)| [name, value])
+                    .map(|s| Boxed(s.into()))
+                    .collect();
+                env.push(Array::new(shape, data));
+            }
             SysOp::FOpen => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let handle = (env.rt.backend)
@@ -1155,6 +1673,24 @@ impl SysOp {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 env.rt.backend.trash(&path).map_err(|e| env.error(e))?;
             }
+            SysOp::FRename => {
+                let from = env.pop(1)?.as_string(env, "Source path must be a string")?;
+                let to = env
+                    .pop(2)?
+                    .as_string(env, "Destination path must be a string")?;
+                (env.rt.backend)
+                    .rename(&from, &to)
+                    .map_err(|e| env.error(e))?;
+            }
+            SysOp::FCopy => {
+                let from = env.pop(1)?.as_string(env, "Source path must be a string")?;
+                let to = env
+                    .pop(2)?
+                    .as_string(env, "Destination path must be a string")?;
+                (env.rt.backend)
+                    .copy_file(&from, &to)
+                    .map_err(|e| env.error(e))?;
+            }
             SysOp::ReadStr => {
                 let count = env
                     .pop(1)?
@@ -1191,7 +1727,9 @@ impl SysOp {
                                 }
                             }
                         } else {
-                            return Err(env.error("Cannot read an infinite amount from stdin"));
+                            let bytes =
+                                env.rt.backend.scan_all_stdin().map_err(|e| env.error(e))?;
+                            String::from_utf8(bytes).map_err(|e| env.error(e))?
                         }
                     }
                     _ => {
@@ -1246,7 +1784,7 @@ impl SysOp {
                         if let Some(count) = count {
                             env.rt.backend.scan_stdin(count).map_err(|e| env.error(e))?
                         } else {
-                            return Err(env.error("Cannot read an infinite amount from stdin"));
+                            env.rt.backend.scan_all_stdin().map_err(|e| env.error(e))?
                         }
                     }
                     _ => {
@@ -1262,6 +1800,28 @@ impl SysOp {
                 };
                 env.push(Array::from(bytes.as_slice()));
             }
+            SysOp::ReadLine => {
+                let handle = env.pop(1)?.as_handle(env, "")?;
+                let line = match handle {
+                    Handle::STDOUT => return Err(env.error("Cannot read from stdout")),
+                    Handle::STDERR => return Err(env.error("Cannot read from stderr")),
+                    Handle::STDIN => env.rt.backend.scan_line_stdin(),
+                    _ => env.rt.backend.read_until(handle, b"\n").map(|bytes| {
+                        if bytes.is_empty() {
+                            None
+                        } else {
+                            let s = String::from_utf8_lossy(&bytes);
+                            Some(s.trim_end_matches(['\n', '\r']).to_string())
+                        }
+                    }),
+                }
+                .map_err(|e| env.error(e))?;
+                if let Some(line) = line {
+                    env.push(line);
+                } else {
+                    env.push(0u8);
+                }
+            }
             SysOp::ReadUntil => {
                 let delim = env.pop(1)?;
                 let handle = env.pop(2)?.as_handle(env, "")?;
@@ -1384,17 +1944,7 @@ impl SysOp {
             }
             SysOp::FWriteAll => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
-                let data = env.pop(2)?;
-                let bytes: Vec<u8> = match data {
-                    Value::Num(arr) => arr.data.iter().map(|&x| x as u8).collect(),
-                    Value::Byte(arr) => arr.data.into(),
-
-                    Value::Complex(_) => {
-                        return Err(env.error("Cannot write complex array to file"))
-                    }
-                    Value::Char(arr) => arr.data.iter().collect::<String>().into(),
-                    Value::Box(_) => return Err(env.error("Cannot write box array to file")),
-                };
+                let bytes = file_bytes(env.pop(2)?, env)?;
                 (env.rt.backend)
                     .file_write_all(path.as_ref(), &bytes)
                     .or_else(|e| {
@@ -1408,16 +1958,34 @@ impl SysOp {
                     })
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::FAppend => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let bytes = file_bytes(env.pop(2)?, env)?;
+                (env.rt.backend)
+                    .file_append_all(path.as_ref(), &bytes)
+                    .map_err(|e| env.error(e))?;
+            }
             SysOp::FExists => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let exists = env.rt.backend.file_exists(&path);
                 env.push(exists);
             }
+            SysOp::FStat => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let (size, modified, is_dir) =
+                    env.rt.backend.file_stat(&path).map_err(|e| env.error(e))?;
+                env.push(cowslice![size as f64, modified, is_dir as u8 as f64]);
+            }
             SysOp::FListDir => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let paths = env.rt.backend.list_dir(&path).map_err(|e| env.error(e))?;
                 env.push(Array::<Boxed>::from_iter(paths));
             }
+            SysOp::FGlob => {
+                let pattern = env.pop(1)?.as_string(env, "Pattern must be a string")?;
+                let paths = env.rt.backend.glob(&pattern).map_err(|e| env.error(e))?;
+                env.push(Array::<Boxed>::from_iter(paths));
+            }
             SysOp::FIsFile => {
                 let path = env.pop(1)?.as_string(env, "Path must be a string")?;
                 let is_file = env.rt.backend.is_file(&path).map_err(|e| env.error(e))?;
@@ -1439,6 +2007,10 @@ impl SysOp {
                 #[cfg(not(feature = "image"))]
                 return Err(env.error("Image encoding is not supported in this environment"));
             }
+            SysOp::ImValid => {
+                let value = env.pop(1)?;
+                env.push(crate::encode::image_dims(&value).is_ok());
+            }
             SysOp::GifShow => {
                 #[cfg(feature = "gif")]
                 {
@@ -1517,6 +2089,9 @@ impl SysOp {
             }
             SysOp::Sleep => {
                 let mut seconds = env.pop(1)?.as_num(env, "Sleep time must be a number")?;
+                if seconds.is_nan() {
+                    return Err(env.error("Sleep time cannot be NaN"));
+                }
                 if seconds < 0.0 {
                     return Err(env.error("Sleep time must be positive"));
                 }
@@ -1526,7 +2101,7 @@ impl SysOp {
                 if let Some(limit) = env.rt.execution_limit {
                     let elapsed = env.rt.backend.now() - env.rt.execution_start;
                     let max = limit - elapsed;
-                    seconds = seconds.min(max);
+                    seconds = seconds.min(max).max(0.0);
                 }
                 env.rt.backend.sleep(seconds).map_err(|e| env.error(e))?;
             }
@@ -1628,6 +2203,13 @@ impl SysOp {
                     .map_err(|e| env.error(e))?;
                 env.push(res);
             }
+            SysOp::HttpGet => {
+                let url = env.pop(1)?.as_string(env, "URL must be a string")?;
+                let bytes = (env.rt.backend)
+                    .http_get(&url, env.rt.http_max_bytes)
+                    .map_err(|e| env.error(e))?;
+                env.push(Array::<u8>::from(bytes.as_slice()));
+            }
             SysOp::Close => {
                 let handle = env.pop(1)?.as_handle(env, "")?;
                 env.rt.backend.close(handle).map_err(|e| env.error(e))?;
@@ -1650,6 +2232,22 @@ impl SysOp {
                 env.push(stdout);
                 env.push(code);
             }
+            SysOp::RunFile => {
+                let path = env.pop(1)?.as_string(env, "Path must be a string")?;
+                let bytes = (env.rt.backend)
+                    .file_read_all(path.as_ref())
+                    .map_err(|e| env.error(e))?;
+                let input = String::from_utf8(bytes).map_err(|e| env.error(e))?;
+                let mut sub = Uiua::with_backend(env.rt.backend.clone())
+                    .with_file_path(&path)
+                    .with_args(env.args().to_owned());
+                sub.compile_run(|comp| comp.load_str_src(&input, Path::new(&path)))
+                    .map_err(|e| {
+                        e.with_info([(format!("Error running {path}"), Some(env.span()))])
+                    })?;
+                let data: CowSlice<Boxed> = sub.take_stack().into_iter().map(Boxed).collect();
+                env.push(Array::new(data.len(), data));
+            }
             SysOp::RunStream => {
                 let (command, args) = value_to_command(&env.pop(1)?, env)?;
                 let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
@@ -1674,6 +2272,12 @@ impl SysOp {
                     .change_directory(&path)
                     .map_err(|e| env.error(e))?;
             }
+            SysOp::CurrentDirectory => {
+                let path = (env.rt.backend)
+                    .current_directory()
+                    .map_err(|e| env.error(e))?;
+                env.push(path);
+            }
             SysOp::WebcamCapture => {
                 let index = env.pop(1)?.as_nat(env, "Webcam index must be an integer")?;
                 let _image = (env.rt.backend)
@@ -1827,6 +2431,17 @@ fn value_to_command(value: &Value, env: &Uiua) -> UiuaResult<(String, Vec<String
     Ok((command, strings))
 }
 
+/// Convert a value to the bytes that should be written to a file
+fn file_bytes(data: Value, env: &Uiua) -> UiuaResult<Vec<u8>> {
+    Ok(match data {
+        Value::Num(arr) => arr.data.iter().map(|&x| x as u8).collect(),
+        Value::Byte(arr) => arr.data.into(),
+        Value::Complex(_) => return Err(env.error("Cannot write complex array to file")),
+        Value::Char(arr) => arr.data.iter().collect::<String>().into(),
+        Value::Box(_) => return Err(env.error("Cannot write box array to file")),
+    })
+}
+
 /// Get the current time in seconds
 ///
 /// This function works on both native and web targets.
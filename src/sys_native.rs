@@ -2,7 +2,7 @@ use std::{
     any::Any,
     env::{self, set_current_dir},
     fs::{self, File, OpenOptions},
-    io::{stderr, stdin, stdout, BufReader, Read, Write},
+    io::{stderr, stdin, stdout, BufRead, BufReader, Read, Write},
     net::*,
     path::{Path, PathBuf},
     process::{Child, ChildStderr, ChildStdin, ChildStdout, Command, Stdio},
@@ -12,19 +12,33 @@ use std::{
         Arc,
     },
     thread::sleep,
-    time::Duration,
+    time::{Duration, UNIX_EPOCH},
 };
 
-use crate::{terminal_size, GitTarget, Handle, SysBackend};
+use crate::{terminal_size, GitTarget, Handle, SysBackend, TextColor, TextStyle};
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 
+#[cfg(feature = "tls")]
+static CLIENT_CONFIG: Lazy<Arc<rustls::ClientConfig>> = Lazy::new(|| {
+    let mut store = rustls::RootCertStore::empty();
+    store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    rustls::ClientConfig::builder()
+        .with_root_certificates(store)
+        .with_no_client_auth()
+        .into()
+});
+
+/// The size at which buffered stdout is written even if it has not been flushed
+const STDOUT_BUFFER_CAPACITY: usize = 64 * 1024;
+
 /// The default native system backend
 #[derive(Default)]
 pub struct NativeSys;
 
 struct GlobalNativeSys {
     output_enabled: AtomicBool,
+    stdout_buffer: parking_lot::Mutex<Option<Vec<u8>>>,
     next_handle: AtomicU64,
     files: DashMap<Handle, BufReader<File>>,
     child_stdins: DashMap<Handle, ChildStream<ChildStdin>>,
@@ -47,6 +61,8 @@ struct GlobalNativeSys {
     ffi: crate::FfiState,
     #[cfg(all(feature = "gif", feature = "invoke"))]
     gifs_child: parking_lot::Mutex<Option<Child>>,
+    #[cfg(feature = "http")]
+    preview_image: parking_lot::Mutex<Option<PathBuf>>,
 }
 
 enum SysStream<'a> {
@@ -200,6 +216,7 @@ impl Default for GlobalNativeSys {
     fn default() -> Self {
         Self {
             output_enabled: AtomicBool::new(true),
+            stdout_buffer: parking_lot::Mutex::new(None),
             next_handle: Handle::FIRST_UNRESERVED.0.into(),
             files: DashMap::new(),
             child_stdins: DashMap::new(),
@@ -222,6 +239,8 @@ impl Default for GlobalNativeSys {
             ffi: Default::default(),
             #[cfg(all(feature = "gif", feature = "invoke"))]
             gifs_child: parking_lot::Mutex::new(None),
+            #[cfg(feature = "http")]
+            preview_image: parking_lot::Mutex::new(None),
         }
     }
 }
@@ -293,10 +312,34 @@ pub fn set_audio_stream_time_port(port: u16) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Write shown images to a file instead of displaying them
+///
+/// This is used by `uiua watch --serve`, which serves the file.
+#[cfg(all(feature = "http", feature = "binary"))]
+#[doc(hidden)]
+pub fn set_preview_image_path(path: PathBuf) {
+    *NATIVE_SYS.preview_image.lock() = Some(path);
+}
+
 pub(crate) fn output_enabled() -> bool {
     NATIVE_SYS.output_enabled.load(atomic::Ordering::Relaxed)
 }
 
+/// Set whether the native backend buffers stdout
+///
+/// Buffered output is written when [`SysOp::Flush`](crate::SysOp::Flush) is called,
+/// before reading from stdin or writing to stderr, and when the buffer gets large.
+/// Hosts should flush it with [`SysBackend::flush_stdout`] when the program ends.
+pub fn set_buffered_output(buffered: bool) {
+    let mut stdout = stdout().lock();
+    let mut buffer = NATIVE_SYS.stdout_buffer.lock();
+    if buffered {
+        buffer.get_or_insert_with(Vec::new);
+    } else if let Some(buffer) = buffer.take() {
+        _ = stdout.write_all(&buffer);
+    }
+}
+
 pub(crate) fn set_output_enabled(enabled: bool) -> bool {
     NATIVE_SYS
         .output_enabled
@@ -315,21 +358,62 @@ impl SysBackend for NativeSys {
             return Ok(());
         }
         let mut stdout = stdout().lock();
+        if let Some(buffer) = &mut *NATIVE_SYS.stdout_buffer.lock() {
+            buffer.extend_from_slice(s.as_bytes());
+            if buffer.len() >= STDOUT_BUFFER_CAPACITY {
+                stdout.write_all(buffer).map_err(|e| e.to_string())?;
+                buffer.clear();
+            }
+            return Ok(());
+        }
         stdout.write_all(s.as_bytes()).map_err(|e| e.to_string())?;
         stdout.flush().map_err(|e| e.to_string())
     }
+    fn flush_stdout(&self) -> Result<(), String> {
+        let mut stdout = stdout().lock();
+        if let Some(buffer) = &mut *NATIVE_SYS.stdout_buffer.lock() {
+            stdout.write_all(buffer).map_err(|e| e.to_string())?;
+            buffer.clear();
+        }
+        stdout.flush().map_err(|e| e.to_string())
+    }
     fn print_str_stderr(&self, s: &str) -> Result<(), String> {
         if !output_enabled() {
             return Ok(());
         }
+        self.flush_stdout()?;
         let mut stderr = stderr().lock();
         stderr.write_all(s.as_bytes()).map_err(|e| e.to_string())?;
         stderr.flush().map_err(|e| e.to_string())
     }
+    fn bell(&self) -> Result<(), String> {
+        self.print_str_stdout("\x07")
+    }
+    fn print_styled(&self, s: &str, style: TextStyle) -> Result<(), String> {
+        use colored::{Color, Colorize};
+        let mut text = s.normal();
+        if let Some(color) = style.color {
+            text = text.color(match color {
+                TextColor::Black => Color::Black,
+                TextColor::Red => Color::Red,
+                TextColor::Green => Color::Green,
+                TextColor::Yellow => Color::Yellow,
+                TextColor::Blue => Color::Blue,
+                TextColor::Magenta => Color::Magenta,
+                TextColor::Cyan => Color::Cyan,
+                TextColor::White => Color::White,
+            });
+        }
+        if style.bold {
+            text = text.bold();
+        }
+        self.print_str_stdout(&text.to_string())
+    }
     fn print_str_trace(&self, s: &str) {
         if !output_enabled() {
             return;
         }
+        _ = self.flush_stdout();
         eprint!("{s}");
         _ = stderr().flush();
     }
@@ -337,6 +421,7 @@ impl SysBackend for NativeSys {
         if !output_enabled() {
             return Ok(None);
         }
+        self.flush_stdout()?;
         let mut buffer = Vec::new();
         let mut b = 0u8;
         loop {
@@ -357,6 +442,16 @@ impl SysBackend for NativeSys {
         }
         Ok(Some(String::from_utf8(buffer).map_err(|e| e.to_string())?))
     }
+    fn scan_lines_stdin(&self) -> Result<Vec<String>, String> {
+        if !output_enabled() {
+            return Ok(Vec::new());
+        }
+        stdin()
+            .lock()
+            .lines()
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())
+    }
     fn scan_stdin(&self, count: usize) -> Result<Vec<u8>, String> {
         if !output_enabled() {
             return Ok(Vec::new());
@@ -365,14 +460,23 @@ impl SysBackend for NativeSys {
         stdin().read_exact(&mut buffer).map_err(|e| e.to_string())?;
         Ok(buffer)
     }
+    fn scan_all_stdin(&self) -> Result<Vec<u8>, String> {
+        if !output_enabled() {
+            return Ok(Vec::new());
+        }
+        let mut buffer = Vec::new();
+        (stdin().lock().read_to_end(&mut buffer)).map_err(|e| e.to_string())?;
+        Ok(buffer)
+    }
     fn save_error_color(&self, message: String, colored: String) {
         NATIVE_SYS.colored_errors.insert(message, colored);
     }
     fn term_size(&self) -> Result<(usize, usize), String> {
-        let (w, h) = terminal_size().ok_or("Failed to get terminal size")?;
-        Ok((w, h.saturating_sub(1)))
+        // Fall back to a standard terminal size when not attached to one
+        Ok(terminal_size().map_or((80, 24), |(w, h)| (w, h.saturating_sub(1))))
     }
     fn exit(&self, code: i32) -> Result<(), String> {
+        _ = self.flush_stdout();
         std::process::exit(code)
     }
     #[cfg(feature = "raw_mode")]
@@ -394,6 +498,9 @@ impl SysBackend for NativeSys {
     fn var(&self, name: &str) -> Option<String> {
         env::var(name).ok()
     }
+    fn vars(&self) -> Vec<(String, String)> {
+        env::vars().collect()
+    }
     fn file_exists(&self, path: &str) -> bool {
         fs::metadata(path).is_ok()
     }
@@ -402,6 +509,14 @@ impl SysBackend for NativeSys {
             .map(|m| m.is_file())
             .map_err(|e| e.to_string())
     }
+    fn file_stat(&self, path: &str) -> Result<(u64, f64, bool), String> {
+        let meta = fs::metadata(path).map_err(|e| format!("{e} {path}"))?;
+        let modified = (meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0.0, |dur| dur.as_secs_f64() * 1000.0);
+        Ok((meta.len(), modified, meta.is_dir()))
+    }
     fn list_dir(&self, path: &str) -> Result<Vec<String>, String> {
         let mut paths = Vec::new();
         for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
@@ -410,6 +525,15 @@ impl SysBackend for NativeSys {
         }
         Ok(paths)
     }
+    fn glob(&self, pattern: &str) -> Result<Vec<String>, String> {
+        let mut paths = Vec::new();
+        for entry in glob::glob(pattern).map_err(|e| e.to_string())? {
+            let path = entry.map_err(|e| e.to_string())?;
+            paths.push(path.to_string_lossy().into());
+        }
+        paths.sort();
+        Ok(paths)
+    }
     fn open_file(&self, path: &Path, write: bool) -> Result<Handle, String> {
         let handle = NATIVE_SYS.new_handle();
         let file = OpenOptions::new()
@@ -426,6 +550,17 @@ impl SysBackend for NativeSys {
         self.close(handle)?;
         Ok(bytes)
     }
+    fn file_write_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        write_atomic(path, contents).map_err(|e| format!("{e} {}", path.display()))
+    }
+    fn file_append_all(&self, path: &Path, contents: &[u8]) -> Result<(), String> {
+        OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(contents))
+            .map_err(|e| format!("{e} {}", path.display()))
+    }
     fn create_file(&self, path: &Path) -> Result<Handle, String> {
         let handle = NATIVE_SYS.new_handle();
         let file = File::create(path).map_err(|e| e.to_string())?;
@@ -433,12 +568,15 @@ impl SysBackend for NativeSys {
         Ok(handle)
     }
     fn make_dir(&self, path: &Path) -> Result<(), String> {
+        if path.is_file() {
+            return Err(format!("{} already exists as a file", path.display()));
+        }
         fs::create_dir_all(path).map_err(|e| e.to_string())
     }
     fn delete(&self, path: &str) -> Result<(), String> {
         let path = Path::new(path);
         if !path.exists() {
-            return Ok(());
+            return Err(format!("{} does not exist", path.display()));
         }
         if path.is_dir() {
             fs::remove_dir_all(path).map_err(|e| e.to_string())
@@ -446,6 +584,23 @@ impl SysBackend for NativeSys {
             fs::remove_file(path).map_err(|e| e.to_string())
         }
     }
+    fn rename(&self, from: &str, to: &str) -> Result<(), String> {
+        let (from, to) = (Path::new(from), Path::new(to));
+        fs::rename(from, to).or_else(|e| {
+            // Renaming fails across filesystems, so fall back to copying and deleting
+            if !from.is_file() {
+                return Err(e.to_string());
+            }
+            fs::copy(from, to)
+                .and_then(|_| fs::remove_file(from))
+                .map_err(|e| format!("Failed to move {} to {}: {e}", from.display(), to.display()))
+        })
+    }
+    fn copy_file(&self, from: &str, to: &str) -> Result<(), String> {
+        fs::copy(from, to)
+            .map(drop)
+            .map_err(|e| format!("Failed to copy {from} to {to}: {e}"))
+    }
     #[cfg(feature = "trash")]
     fn trash(&self, path: &str) -> Result<(), String> {
         trash::delete(path).map_err(|e| e.to_string())
@@ -568,54 +723,29 @@ impl SysBackend for NativeSys {
         sleep(Duration::from_secs_f64(seconds));
         Ok(())
     }
-    #[cfg(all(feature = "terminal_image", feature = "image"))]
+    #[cfg(all(
+        feature = "image",
+        any(feature = "terminal_image", feature = "viewer", feature = "http")
+    ))]
     fn show_image(&self, image: image::DynamicImage, _: Option<&str>) -> Result<(), String> {
-        let (width, height) = if let Some((w, h)) = terminal_size() {
-            let (tw, th) = (w as u32, h.saturating_sub(1) as u32);
-            let (iw, ih) = (image.width(), (image.height() / 2).max(1));
-            let scaled_to_height = (iw * th / ih.max(1), th);
-            let scaled_to_width = (tw, ih * tw / iw.max(1));
-            let (w, h) = if scaled_to_height.0 <= tw {
-                scaled_to_height
-            } else {
-                scaled_to_width
-            };
-            (Some(w), Some(h))
-        } else {
-            (None, None)
-        };
-        if std::env::var("TERM")
-            .unwrap_or("".to_owned())
-            .contains("sixel")
-            || std::env::var("UIUA_ENABLE_SIXEL").is_ok_and(|s| s == "1")
+        // `uiua watch --serve` routes images to a file that it serves
+        #[cfg(feature = "http")]
+        if let Some(path) = &*NATIVE_SYS.preview_image.lock() {
+            return write_atomic(path, &encode_png(&image)?)
+                .map_err(|e| format!("Failed to write preview image: {e}"));
+        }
+        #[cfg(feature = "terminal_image")]
         {
-            let img_rgba8 = image.to_rgba8();
-            let sixel = icy_sixel::sixel_string(
-                image.to_rgba8().as_raw(),
-                img_rgba8.width() as i32,
-                img_rgba8.height() as i32,
-                icy_sixel::PixelFormat::RGBA8888,
-                icy_sixel::DiffusionMethod::Stucki,
-                icy_sixel::MethodForLargest::Auto,
-                icy_sixel::MethodForRep::Auto,
-                icy_sixel::Quality::HIGH,
-            );
-            let s = sixel.map_err(|e| e.to_string())?;
-            print!("{s}");
-            Ok(())
-        } else {
-            viuer::print(
-                &image,
-                &viuer::Config {
-                    width,
-                    height,
-                    absolute_offset: false,
-                    transparent: true,
-                    ..Default::default()
-                },
-            )
-            .map(drop)
-            .map_err(|e| format!("Failed to show image: {e}"))
+            show_terminal_image(image)
+        }
+        #[cfg(all(feature = "viewer", not(feature = "terminal_image")))]
+        {
+            open_image_viewer(image)
+        }
+        #[cfg(not(any(feature = "terminal_image", feature = "viewer")))]
+        {
+            _ = image;
+            Err("Showing images is not supported in this environment".into())
         }
     }
     #[cfg(all(feature = "gif", feature = "invoke"))]
@@ -933,6 +1063,10 @@ impl SysBackend for NativeSys {
     fn change_directory(&self, path: &str) -> Result<(), String> {
         env::set_current_dir(path).map_err(|e| e.to_string())
     }
+    fn current_directory(&self) -> Result<String, String> {
+        let path = env::current_dir().map_err(|e| e.to_string())?;
+        Ok(path.to_string_lossy().into_owned())
+    }
     #[cfg(feature = "tls")]
     fn https_get(&self, request: &str, handle: Handle) -> Result<String, String> {
         use std::io;
@@ -949,15 +1083,6 @@ impl SysBackend for NativeSys {
         let mut buffer = Vec::new();
         let port = stream.peer_addr().map(|a| a.port());
         if let Ok(443) = port {
-            static CLIENT_CONFIG: Lazy<std::sync::Arc<rustls::ClientConfig>> = Lazy::new(|| {
-                let mut store = rustls::RootCertStore::empty();
-                store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
-                rustls::ClientConfig::builder()
-                    .with_root_certificates(store)
-                    .with_no_client_auth()
-                    .into()
-            });
-
             let server_name =
                 rustls::pki_types::ServerName::try_from(host).map_err(|e| e.to_string())?;
             let mut conn = rustls::ClientConnection::new(CLIENT_CONFIG.clone(), server_name)
@@ -981,6 +1106,85 @@ impl SysBackend for NativeSys {
 
         Ok(s)
     }
+    #[cfg(feature = "http")]
+    fn http_get(&self, url: &str, max_bytes: u64) -> Result<Vec<u8>, String> {
+        use std::io;
+
+        let (https, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            return Err(format!(
+                "Invalid URL {url:?}, expected it to start with http:// or https://"
+            ));
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = (port.parse::<u16>()).map_err(|_| format!("Invalid port {port:?}"))?;
+                (host, port)
+            }
+            None => (authority, if https { 443 } else { 80 }),
+        };
+        let request = format!("GET {path} HTTP/1.0\r\nHost: {host}\r\nConnection: close\r\n\r\n");
+
+        let mut stream = TcpStream::connect((host, port)).map_err(|e| e.to_string())?;
+        let mut response = Vec::new();
+        let read_limited = |reader: &mut dyn Read, response: &mut Vec<u8>| match reader
+            .take(max_bytes.saturating_add(1))
+            .read_to_end(response)
+        {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(()),
+            Err(e) => Err(e.to_string()),
+        };
+        if https {
+            #[cfg(feature = "tls")]
+            {
+                let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+                    .map_err(|e| e.to_string())?;
+                let mut conn = rustls::ClientConnection::new(CLIENT_CONFIG.clone(), server_name)
+                    .map_err(|e| e.to_string())?;
+                let mut tls = rustls::Stream::new(&mut conn, &mut stream);
+                tls.write_all(request.as_bytes())
+                    .map_err(|e| e.to_string())?;
+                read_limited(&mut tls, &mut response)?;
+            }
+            #[cfg(not(feature = "tls"))]
+            return Err("Making HTTPS requests is not supported in this environment".into());
+        } else {
+            (stream.write_all(request.as_bytes())).map_err(|e| e.to_string())?;
+            read_limited(&mut stream, &mut response)?;
+        }
+        if response.len() as u64 > max_bytes {
+            return Err(format!(
+                "HTTP response exceeded the maximum size of {max_bytes} bytes"
+            ));
+        }
+
+        let mut headers = [httparse::EMPTY_HEADER; 64];
+        let mut res = httparse::Response::new(&mut headers);
+        let body_start = match res.parse(&response) {
+            Ok(httparse::Status::Complete(len)) => len,
+            Ok(httparse::Status::Partial) => return Err("Incomplete HTTP response".into()),
+            Err(e) => return Err(format!("Failed to parse HTTP response: {e}")),
+        };
+        let code = res.code.unwrap_or(0);
+        if !(200..300).contains(&code) {
+            return Err(match res.reason {
+                Some(reason) if !reason.is_empty() => {
+                    format!("HTTP request failed with status {code} {reason}")
+                }
+                _ => format!("HTTP request failed with status {code}"),
+            });
+        }
+        response.drain(..body_start);
+        Ok(response)
+    }
     #[cfg(feature = "webcam")]
     fn webcam_capture(&self, index: usize) -> Result<crate::WebcamImage, String> {
         let cam_channels = &NATIVE_SYS.cam_channels;
@@ -1133,6 +1337,121 @@ impl SysBackend for NativeSys {
     }
 }
 
+#[cfg(all(
+    feature = "image",
+    any(feature = "terminal_image", feature = "viewer", feature = "http")
+))]
+fn encode_png(image: &image::DynamicImage) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageOutputFormat::Png,
+        )
+        .map_err(|e| format!("Failed to encode image: {e}"))?;
+    Ok(bytes)
+}
+
+/// Print an image to the terminal, using sixel if it is supported
+#[cfg(all(feature = "terminal_image", feature = "image"))]
+fn show_terminal_image(image: image::DynamicImage) -> Result<(), String> {
+    let (width, height) = if let Some((w, h)) = terminal_size() {
+        let (tw, th) = (w as u32, h.saturating_sub(1) as u32);
+        let (iw, ih) = (image.width(), (image.height() / 2).max(1));
+        let scaled_to_height = (iw * th / ih.max(1), th);
+        let scaled_to_width = (tw, ih * tw / iw.max(1));
+        let (w, h) = if scaled_to_height.0 <= tw {
+            scaled_to_height
+        } else {
+            scaled_to_width
+        };
+        (Some(w), Some(h))
+    } else {
+        (None, None)
+    };
+    if std::env::var("TERM")
+        .unwrap_or("".to_owned())
+        .contains("sixel")
+        || std::env::var("UIUA_ENABLE_SIXEL").is_ok_and(|s| s == "1")
+    {
+        let img_rgba8 = image.to_rgba8();
+        let sixel = icy_sixel::sixel_string(
+            image.to_rgba8().as_raw(),
+            img_rgba8.width() as i32,
+            img_rgba8.height() as i32,
+            icy_sixel::PixelFormat::RGBA8888,
+            icy_sixel::DiffusionMethod::Stucki,
+            icy_sixel::MethodForLargest::Auto,
+            icy_sixel::MethodForRep::Auto,
+            icy_sixel::Quality::HIGH,
+        );
+        let s = sixel.map_err(|e| e.to_string())?;
+        print!("{s}");
+        Ok(())
+    } else {
+        viuer::print(
+            &image,
+            &viuer::Config {
+                width,
+                height,
+                absolute_offset: false,
+                transparent: true,
+                ..Default::default()
+            },
+        )
+        .map(drop)
+        .map_err(|e| format!("Failed to show image: {e}"))
+    }
+}
+
+/// Open an image in the system's default image viewer
+#[cfg(all(feature = "viewer", feature = "image", not(feature = "terminal_image")))]
+fn open_image_viewer(image: image::DynamicImage) -> Result<(), String> {
+    let bytes = encode_png(&image)?;
+    // Each image gets its own file so that a viewer that is still open keeps its image
+    static IMAGE_COUNT: AtomicU64 = AtomicU64::new(0);
+    let temp_path = env::temp_dir().join(format!(
+        "uiua-image-{}-{}.png",
+        std::process::id(),
+        IMAGE_COUNT.fetch_add(1, atomic::Ordering::Relaxed)
+    ));
+    fs::write(&temp_path, bytes).map_err(|e| format!("Failed to write image: {e}"))?;
+    open::that_detached(&temp_path).map_err(|e| format!("Failed to open image viewer: {e}"))
+}
+
+/// Write a file without ever leaving it partially written
+///
+/// The contents are written to a temporary file in the same directory as the target,
+/// which is then renamed over the target. The temporary file must be in the same
+/// directory because a rename is only atomic within a single filesystem.
+///
+/// If anything fails, the temporary file is removed and the target is left untouched.
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "Path is not a file")
+    })?;
+    let temp_path = dir.join(format!(
+        ".{}.{}.uiua-tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+    let res = (|| {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp_path, path)
+    })();
+    if res.is_err() {
+        _ = fs::remove_file(&temp_path);
+    }
+    res
+}
+
 /// Takes an HTTP request, validates it, and fixes it (if possible) by adding
 /// the HTTP version and trailing newlines if they aren't present.
 ///
@@ -1220,3 +1539,31 @@ fn check_http(mut request: String, hostname: &str) -> Result<String, String> {
 
     Ok(request)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_write() {
+        let dir = env::temp_dir().join(format!("uiua-atomic-write-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.txt");
+        fs::write(&path, "original").unwrap();
+
+        // A failed write leaves the target untouched
+        let bad_path = dir.join("missing").join("out.txt");
+        assert!(NativeSys.file_write_all(&bad_path, b"new").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+
+        // A successful write replaces the target in full
+        NativeSys.file_write_all(&path, b"replaced").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "replaced");
+
+        // No temporary files are left behind
+        let entries: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -269,6 +269,8 @@ impl Value {
         val_as_arr!(self, |array| array.first_dim_zero().into())
     }
     /// Get the rank
+    ///
+    /// Scalars have rank `0`.
     pub fn rank(&self) -> usize {
         self.shape().len()
     }
@@ -304,6 +306,8 @@ impl Value {
         &mut *(self as *mut Self as *mut Repr)
     }
     /// Get the shape of the value
+    ///
+    /// [`Shape`] dereferences to `[usize]`. Scalars have an empty shape.
     pub fn shape(&self) -> &Shape {
         &unsafe { self.repr() }.arr.shape
     }
@@ -548,6 +552,37 @@ impl Value {
             value => value.grid_string(false),
         }
     }
+    /// Get the tab-separated representation of the value
+    ///
+    /// Rank-`2` arrays put each row on its own line, and rank-`1` arrays are a single line.
+    /// Character arrays are treated as lists of strings, and boxes are unboxed.
+    /// Tabs and newlines in cells are escaped.
+    /// Returns `None` if the value has too many dimensions to be a table.
+    pub fn to_tsv(&self) -> Option<String> {
+        fn cell(value: Value) -> String {
+            (value.unboxed().format())
+                .replace('\t', "\\t")
+                .replace('\n', "\\n")
+        }
+        fn line(value: &Value) -> String {
+            value.rows().map(cell).collect::<Vec<_>>().join("\t")
+        }
+        let rank = match self {
+            Value::Char(arr) => arr.rank().saturating_sub(1),
+            value => value.rank(),
+        };
+        match rank {
+            0 => Some(cell(self.clone())),
+            1 => Some(line(self)),
+            2 => Some(
+                self.rows()
+                    .map(|row| line(&row))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            _ => None,
+        }
+    }
     /// Attempt to convert the array to a list of integers
     ///
     /// The `requirement` parameter is used in error messages.